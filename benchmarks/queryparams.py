@@ -0,0 +1,105 @@
+"""
+Micro-benchmarks for the native `QueryParams` and quoting code.
+
+Build the extension in release mode first (`maturin develop --release`), then run
+`scripts/benchmark`, optionally naming the benchmarks to run. Timings are the best
+of five runs. The memory benchmark reads `/proc`, so it needs Linux. Measurements
+of past changes are kept in `results.md`.
+"""
+
+from __future__ import annotations
+
+import os
+import subprocess
+import sys
+import timeit
+import typing
+
+import httpx
+from httpx._httpx import quote
+
+PAIRS_5000 = "&".join(f"key{i}=value{i}" for i in range(5000))
+DICT_5000 = {f"key{i}": f"value{i}" for i in range(5000)}
+LEFT_500 = httpx.QueryParams({f"left{i}": str(i) for i in range(500)})
+RIGHT_500 = httpx.QueryParams(
+    {f"right{i}": str(i) for i in range(250)} | {f"left{i}": "r" for i in range(250)}
+)
+PARAMS_100 = httpx.QueryParams({f"key{i}": f"value{i}" for i in range(100)})
+BYTES_3MB = "&".join(f"k{i}=caf%C3%A9+{i}" for i in range(200_000)).encode("ascii")
+CLEAN_SEGMENTS = [
+    "/api/v1/users/12345",
+    "/static/css/site.min.css",
+    "example.com",
+    "index.html",
+    "/search",
+    "v2",
+    "/repos/encode/httpx/pulls",
+    "page-2",
+] * 1000
+
+
+def rss_kib() -> int:
+    with open("/proc/self/statm") as statm:
+        pages = int(statm.read().split()[1])
+    return pages * os.sysconf("SC_PAGE_SIZE") // 1024
+
+
+def memory_10k(keys: list[str]) -> int:
+    before = rss_kib()
+    objects = [
+        httpx.QueryParams({keys[0]: i, keys[1]: 20, keys[2]: "id"})
+        for i in range(10_000)
+    ]
+    after = rss_kib()
+    assert len(objects) == 10_000
+    return after - before
+
+
+def memory_10k_common_keys() -> str:
+    # Each set is measured in a fresh interpreter, so neither reuses pages the other
+    # freed. The second has keys of the same lengths that are not interned, so each
+    # object allocates its own copies.
+    results = [
+        subprocess.run(
+            [sys.executable, __file__, "--memory", *keys],
+            capture_output=True,
+            check=True,
+            text=True,
+        ).stdout.strip()
+        for keys in (["page", "per_page", "sort"], ["pagx", "per_pagx", "sorx"])
+    ]
+    return f"{results[0]} KiB (uncommon keys: {results[1]} KiB)"
+
+
+BENCHMARKS: dict[str, tuple[typing.Callable[[], object], int]] = {
+    "parse_str_5000": (lambda: httpx.QueryParams(PAIRS_5000), 100),
+    "from_dict_5000": (lambda: httpx.QueryParams(DICT_5000), 100),
+    "merge_500": (lambda: LEFT_500.merge(RIGHT_500), 1000),
+    "set_add_100_keys": (
+        lambda: PARAMS_100.set("key50", "new").add("key99", "more"),
+        10_000,
+    ),
+    "parse_bytes_3mb": (lambda: httpx.QueryParams(BYTES_3MB), 5),
+    "quote_clean_8000": (
+        lambda: [quote(segment, "/") for segment in CLEAN_SEGMENTS],
+        20,
+    ),
+}
+
+
+def main(names: list[str]) -> None:
+    # Memory is measured first, before other benchmarks leave freed pages behind.
+    for name in names or ["memory_10k_common_keys", *BENCHMARKS]:
+        if name == "memory_10k_common_keys":
+            print(f"{name:24} {memory_10k_common_keys()}")
+            continue
+        func, number = BENCHMARKS[name]
+        best = min(timeit.repeat(func, number=number, repeat=5)) / number
+        print(f"{name:24} {best * 1e6:10.1f} us")
+
+
+if __name__ == "__main__":
+    if sys.argv[1:2] == ["--memory"]:
+        print(memory_10k(sys.argv[2:]))
+    else:
+        main(sys.argv[1:])
@@ -72,6 +72,8 @@ __all__ = [
     "ProxyError",
     "put",
     "QueryParams",
+    "QueryParamsBuilder",
+    "QueryParamsTooLarge",
     "ReadError",
     "ReadTimeout",
     "RemoteProtocolError",
@@ -9,7 +9,7 @@ from base64 import b64encode
 from urllib.request import parse_http_list
 
 from ._exceptions import ProtocolError
-from ._httpx import unquote
+from ._httpx import strip_quotes
 from ._models import Cookies, Request, Response
 from ._utils import to_bytes, to_str
 
@@ -238,7 +238,7 @@ class DigestAuth(Auth):
         header_dict: dict[str, str] = {}
         for field in parse_http_list(fields):
             key, value = field.strip().split("=", 1)
-            header_dict[key] = unquote(value)
+            header_dict[key] = strip_quotes(value)
 
         try:
             realm = header_dict["realm"].encode()
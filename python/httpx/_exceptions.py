@@ -24,6 +24,7 @@ Our exception hierarchy:
   x HTTPStatusError
 * InvalidURL
 * CookieConflict
+* QueryParamsTooLarge
 * StreamError
   x StreamConsumed
   x StreamClosed
@@ -36,7 +37,7 @@ from __future__ import annotations
 import contextlib
 import typing
 
-from ._httpx import InvalidURL
+from ._httpx import InvalidURL, QueryParamsTooLarge
 
 if typing.TYPE_CHECKING:
     from ._models import Request, Response  # pragma: no cover
@@ -55,6 +56,7 @@ __all__ = [
     "PoolTimeout",
     "ProtocolError",
     "ProxyError",
+    "QueryParamsTooLarge",
     "ReadError",
     "ReadTimeout",
     "RemoteProtocolError",
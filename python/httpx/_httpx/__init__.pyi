@@ -10,11 +10,123 @@ QueryParamTypes = typing.Union[
     bytes,
 ]
 
+BoolFormat = typing.Literal["lower", "python", "int"]
+
 @typing.final
 class QueryParams(typing.Mapping[str, str]):
     def __new__(
         cls, *args: QueryParamTypes | None, **kwargs: typing.Any
     ) -> QueryParams: ...
+    @classmethod
+    def with_options(
+        cls,
+        params: QueryParamTypes | None = None,
+        /,
+        *,
+        drop_none: bool = False,
+        bool_format: BoolFormat = "lower",
+        max_params: int | None = None,
+        max_length: int | None = None,
+    ) -> QueryParams:
+        """
+        Build query params like the constructor, with options that control how
+        `params` is read. Every keyword argument to the constructor is taken as a
+        parameter, so these can only be passed here.
+
+        * `drop_none`: Leave out `None` values, rather than sending them as empty
+            strings.
+        * `bool_format`: Render boolean keys and values as "lower" (`true`),
+            "python" (`True`) or "int" (`1`).
+        * `max_params`, `max_length`: Raise `QueryParamsTooLarge` when `params`
+            holds more pairs, or is a longer query string, than this.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams.with_options({"a": None, "b": True}, drop_none=True)
+        assert str(q) == "b=true"
+        assert str(httpx.QueryParams(drop_none=True)) == "drop_none=true"
+        ```
+        """
+
+    @classmethod
+    def parse(cls, string: str, separator: str = "&") -> QueryParams:
+        """
+        Parse a query string, splitting pairs on the given separator instead of '&'.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams.parse("a=123;b=456", separator=";")
+        assert q == httpx.QueryParams("a=123&b=456")
+        ```
+        """
+
+    def serialize(self, separator: str = "&") -> str:
+        """
+        Return the encoded query string, joining pairs with the given separator.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("a=123&b=456")
+        assert q.serialize(separator=";") == "a=123;b=456"
+        ```
+        """
+
+    def encode(
+        self,
+        safe: str | bytes = "",
+        quote_via: typing.Literal["percent", "plus"] = "percent",
+    ) -> str:
+        """
+        Return the encoded query string, leaving the ASCII characters in `safe`
+        unescaped. Non-ASCII characters in `safe` raise a `ValueError`. Spaces are
+        encoded as `%20`, or as `+` when `quote_via="plus"`.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams({"path": "/a b", "t": "12:00"})
+        assert q.encode() == "path=%2Fa%20b&t=12%3A00"
+        assert q.encode(safe="/:", quote_via="plus") == "path=/a+b&t=12:00"
+        ```
+        """
+
+    def canonical(self) -> str:
+        """
+        Return the canonical query string used for AWS Signature Version 4
+        request signing: pairs sorted by encoded key then encoded value, with
+        every character other than `A-Za-z0-9-_.~` percent-encoded.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("Version=2010-05-08&Action=ListUsers&q=a b")
+        assert q.canonical() == "Action=ListUsers&Version=2010-05-08&q=a%20b"
+        ```
+        """
+
+    @classmethod
+    def builder(cls) -> QueryParamsBuilder:
+        """
+        Return an empty QueryParamsBuilder.
+        """
+
+    def to_builder(self) -> QueryParamsBuilder:
+        """
+        Return a QueryParamsBuilder initialised with a copy of these params.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("a=123")
+        builder = q.to_builder()
+        builder["b"] = "456"
+        assert builder.build() == httpx.QueryParams("a=123&b=456")
+        ```
+        """
+
     def keys(self) -> typing.KeysView[str]:
         """
         Return all the keys in the query params.
@@ -27,6 +139,46 @@ class QueryParams(typing.Mapping[str, str]):
         ```
         """
 
+    def keys_view(self) -> typing.Collection[str]:
+        """
+        Return a view over the keys that supports `len()`, `in` and
+        iteration without copying them into a list, like `dict.keys()`.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("a=123&a=456&b=789")
+        keys = q.keys_view()
+        assert len(keys) == 2
+        assert "a" in keys
+        ```
+        """
+
+    def values_view(self) -> typing.Collection[str]:
+        """
+        Return a view over the first value of each key, like `dict.values()`.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("a=123&a=456&b=789")
+        assert list(q.values_view()) == ["123", "789"]
+        ```
+        """
+
+    def items_view(self) -> typing.Collection[tuple[str, str]]:
+        """
+        Return a view over `(key, value)` pairs with the first value for each
+        key, like `dict.items()`.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("a=123&a=456&b=789")
+        assert ("a", "123") in q.items_view()
+        ```
+        """
+
     def values(self) -> typing.ValuesView[str]:
         """
         Return all the values in the query params. If a key occurs more than once
@@ -63,16 +215,288 @@ class QueryParams(typing.Mapping[str, str]):
         ```
         """
 
-    def get(self, key: typing.Any, default: typing.Any = None) -> typing.Any:
+    def json(self) -> str:
+        """
+        Return the query params as a JSON array of `[key, value]` pairs, keeping
+        duplicate keys and their order.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("a=1&b=2&a=3")
+        assert q.json() == '[["a", "1"], ["a", "3"], ["b", "2"]]'
+        ```
+        """
+
+    @classmethod
+    def from_string(cls, string: str) -> QueryParams:
+        """
+        Parse a query string. Unlike the constructor, only `str` is accepted.
+        """
+
+    @classmethod
+    def from_bytes(cls, data: bytes, encoding: str = "utf-8") -> QueryParams:
+        """
+        Parse a query string from bytes in the given encoding. Only `bytes` is
+        accepted. Percent-escapes are decoded with the same encoding.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams.from_bytes("a=é".encode("latin-1"), encoding="latin-1")
+        assert q["a"] == "é"
+        q = httpx.QueryParams.from_bytes(b"a=%E9", encoding="latin-1")
+        assert q["a"] == "é"
+        ```
+        """
+
+    @classmethod
+    def from_pairs(cls, pairs: typing.Iterable[tuple[str, PrimitiveData]]) -> QueryParams:
+        """
+        Build query params from an iterable of `(key, value)` pairs. Strings,
+        bytes and mappings are rejected with a `TypeError`.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams.from_pairs([("a", "1"), ("a", "2")])
+        assert str(q) == "a=1&a=2"
+        ```
+        """
+
+    @classmethod
+    def from_json(cls, string: str) -> QueryParams:
+        """
+        Parse query params from the output of `json()`.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams.from_json('[["a", "1"], ["b", "2"]]')
+        assert str(q) == "a=1&b=2"
+        ```
+        """
+
+    @classmethod
+    def from_parse_qs(cls, params: dict[str, list[str]]) -> QueryParams:
+        """
+        Build query params from the output of `urllib.parse.parse_qs`.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams.from_parse_qs({"a": ["1", "2"], "b": ["3"]})
+        assert str(q) == "a=1&a=2&b=3"
+        ```
+        """
+
+    @classmethod
+    def from_parse_qsl(cls, params: list[tuple[str, str]]) -> QueryParams:
+        """
+        Build query params from the output of `urllib.parse.parse_qsl`.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams.from_parse_qsl([("a", "1"), ("b", "2")])
+        assert str(q) == "a=1&b=2"
+        ```
+        """
+
+    def to_dict(self) -> dict[str, str]:
+        """
+        Return a dictionary of each key's first value, matching `items()`.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("a=123&a=456&b=789")
+        assert q.to_dict() == {"a": "123", "b": "789"}
+        ```
+        """
+
+    def multi_dict(self) -> dict[str, list[str]]:
+        """
+        Return a dictionary mapping each key to the list of all its values,
+        in the shape returned by `urllib.parse.parse_qs`.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("a=123&a=456&b=789")
+        assert q.multi_dict() == {"a": ["123", "456"], "b": ["789"]}
+        ```
+        """
+
+    def to_parse_qs(self) -> dict[str, list[str]]:
+        """
+        Return the query params in the shape of
+        `urllib.parse.parse_qs(..., keep_blank_values=True)`.
+        """
+
+    def to_parse_qsl(self) -> list[tuple[str, str]]:
+        """
+        Return the query params in the shape of
+        `urllib.parse.parse_qsl(..., keep_blank_values=True)`.
+
+        Unlike `parse_qsl`, which keeps the pairs in input order, repeated keys
+        are grouped together at the position where the key first appears. When
+        a key's values are interleaved with other keys, the result is therefore
+        not a drop-in replacement for `parse_qsl`.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("b=2&a=1&b=3")
+        assert q.to_parse_qsl() == [("b", "2"), ("b", "3"), ("a", "1")]
+        ```
+        """
+
+    def iter_values(self) -> typing.Iterator[str]:
+        """
+        Return an iterator over the first value of each key, like `values()`
+        but without building the full list up front.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("a=123&a=456&b=789")
+        assert list(q.iter_values()) == ["123", "789"]
+        ```
+        """
+
+    def pairs(self) -> typing.Iterator[tuple[str, str]]:
+        """
+        Return an iterator over `(key, value)` pairs, yielding the first value
+        for each key, like `items()`.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("a=123&a=456&b=789")
+        assert list(q.pairs()) == [("a", "123"), ("b", "789")]
+        ```
+        """
+
+    def iter_multi_items(self) -> typing.Iterator[tuple[str, str]]:
+        """
+        Return an iterator over all `(key, value)` pairs, like `multi_items()`
+        but without building the full list up front.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("a=123&a=456&b=789")
+        assert next(q.iter_multi_items()) == ("a", "123")
+        ```
+        """
+
+    def get(self, key: typing.Any, default: typing.Any = None, *, last: bool = False) -> typing.Any:
         """
         Get a value from the query param for a given key. If the key occurs
-        more than once, then only the first value is returned.
+        more than once, then only the first value is returned, or the final
+        one when `last=True`.
 
         Usage:
 
         ```
         q = httpx.QueryParams("a=123&a=456&b=789")
         assert q.get("a") == "123"
+        assert q.get("a", last=True) == "456"
+        ```
+        """
+
+    def get_int(self, key: str, default: typing.Any = None) -> typing.Any:
+        """
+        Get the first value for a given key, parsed as an integer the way `int()`
+        parses it, so values of any size are supported.
+
+        Returns `default` if the key is missing, and raises `ValueError` if
+        the value is not an integer.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("page=2")
+        assert q.get_int("page") == 2
+        assert q.get_int("limit", 10) == 10
+        ```
+        """
+
+    def get_float(self, key: str, default: typing.Any = None) -> typing.Any:
+        """
+        Get the first value for a given key, parsed as a float the way `float()`
+        parses it.
+
+        Returns `default` if the key is missing, and raises `ValueError` if
+        the value is not a float.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("lat=51.5")
+        assert q.get_float("lat") == 51.5
+        ```
+        """
+
+    def get_bool(self, key: str, default: typing.Any = None) -> typing.Any:
+        """
+        Get the first value for a given key, parsed as a boolean.
+
+        Accepts `true`/`false`, `1`/`0` and `yes`/`no`, case-insensitively.
+        Returns `default` if the key is missing, and raises `ValueError` for
+        any other value.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("debug=Yes")
+        assert q.get_bool("debug") is True
+        ```
+        """
+
+    def exists(self, key: str, value: typing.Any) -> bool:
+        """
+        Return whether the given `key=value` pair is present. The value is
+        converted in the same way as `set()`.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("tag=rust&tag=python&active=true")
+        assert q.exists("tag", "python")
+        assert q.exists("active", True)
+        assert not q.exists("tag", "go")
+        ```
+        """
+
+    def get_prefixed(self, prefix: str, strip: bool = False) -> QueryParams:
+        """
+        Return a new QueryParams instance with only the keys starting with
+        `prefix`, optionally removing the prefix from the returned keys.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("filter.name=a&filter.age=3&sort.field=name")
+        assert q.get_prefixed("filter.") == httpx.QueryParams("filter.name=a&filter.age=3")
+        assert q.get_prefixed("filter.", strip=True) == httpx.QueryParams("name=a&age=3")
+        ```
+        """
+
+    def item_at(self, index: int) -> tuple[str, str]:
+        """
+        Return the `(key, value)` pair at a given position in insertion order,
+        with the first value for the key. Negative indices count from the end.
+
+        Raises `IndexError` if the index is out of range.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("a=123&a=456&b=789")
+        assert q.item_at(0) == ("a", "123")
+        assert q.item_at(-1) == ("b", "789")
         ```
         """
 
@@ -88,7 +512,9 @@ class QueryParams(typing.Mapping[str, str]):
         ```
         """
 
-    def set(self, key: str, value: typing.Any = None) -> QueryParams:
+    def set(
+        self, key: str, value: typing.Any = None, *, bool_format: BoolFormat = "lower"
+    ) -> QueryParams:
         """
         Return a new QueryParams instance, setting the value of a key.
 
@@ -101,7 +527,9 @@ class QueryParams(typing.Mapping[str, str]):
         ```
         """
 
-    def add(self, key: str, value: typing.Any = None) -> QueryParams:
+    def add(
+        self, key: str, value: typing.Any = None, *, bool_format: BoolFormat = "lower"
+    ) -> QueryParams:
         """
         Return a new QueryParams instance, setting or appending the value of a key.
 
@@ -114,6 +542,66 @@ class QueryParams(typing.Mapping[str, str]):
         ```
         """
 
+    def setdefault(
+        self, key: str, value: typing.Any = None, *, bool_format: BoolFormat = "lower"
+    ) -> QueryParams:
+        """
+        Return a new QueryParams instance, setting the value of a key only if
+        the key is not already present.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("a=123")
+        q = q.setdefault("a", "456").setdefault("page", 1)
+        assert q == httpx.QueryParams("a=123&page=1")
+        ```
+        """
+
+    def copy_with(self, **kwargs: typing.Any) -> QueryParams:
+        """
+        Return a new QueryParams instance, setting each keyword argument as a
+        value. Passing `None` removes the key instead.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("a=123&page=2&cursor=abc")
+        q = q.copy_with(page=3, cursor=None)
+        assert q == httpx.QueryParams("a=123&page=3")
+        ```
+        """
+
+    def set_list(
+        self, key: str, values: typing.Sequence[PrimitiveData]
+    ) -> QueryParams:
+        """
+        Return a new QueryParams instance, replacing all the values of a key.
+        An existing key keeps its position, and an empty list removes the key.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("a=123&b=456")
+        q = q.set_list("a", ["x", "y"])
+        assert q == httpx.QueryParams("a=x&a=y&b=456")
+        ```
+        """
+
+    def extend(self, pairs: QueryParamTypes) -> QueryParams:
+        """
+        Return a new QueryParams instance, appending every given pair.
+        Existing values are kept, so duplicate keys accumulate.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("a=123")
+        q = q.extend([("a", "456"), ("b", "789")])
+        assert q == httpx.QueryParams("a=123&a=456&b=789")
+        ```
+        """
+
     def remove(self, key: str) -> QueryParams:
         """
         Return a new QueryParams instance, removing the value of a key.
@@ -126,6 +614,89 @@ class QueryParams(typing.Mapping[str, str]):
         ```
         """
 
+    def without(self, keys: str | typing.Iterable[typing.Any]) -> QueryParams:
+        """
+        Return a new QueryParams instance, removing every listed key.
+
+        Keys that are not present are ignored.
+
+        Usage:
+        ```
+        q = httpx.QueryParams("a=1&utm_source=x&b=2&utm_medium=y")
+        q = q.without(["utm_source", "utm_medium"])
+        assert q == httpx.QueryParams("a=1&b=2")
+        ```
+        """
+
+    def filter(self, predicate: typing.Callable[[str, str], typing.Any]) -> QueryParams:
+        """
+        Return a new QueryParams instance, keeping only the pairs for which
+        `predicate(key, value)` is truthy.
+
+        Usage:
+        ```
+        q = httpx.QueryParams("a=1&_internal_id=2&b=")
+        q = q.filter(lambda key, value: value and not key.startswith("_internal"))
+        assert q == httpx.QueryParams("a=1")
+        ```
+        """
+
+    def rename_key(self, old: str, new: str) -> QueryParams:
+        """
+        Return a new QueryParams instance with `old` renamed to `new`, keeping
+        its position and all of its values.
+
+        Raises `ValueError` if `new` is already present. If `old` is missing
+        an unchanged copy is returned.
+
+        Usage:
+        ```
+        q = httpx.QueryParams("q=rust&page=2")
+        q = q.rename_key("q", "query")
+        assert str(q) == "query=rust&page=2"
+        ```
+        """
+
+    def pop(self, key: str, default: typing.Any = ...) -> tuple[typing.Any, QueryParams]:
+        """
+        Return the first value for a key, together with a new QueryParams
+        instance with that key removed. If the key is missing the default is
+        returned, or `KeyError` is raised when no default was given.
+
+        Usage:
+        ```
+        q = httpx.QueryParams("page_token=abc&size=10")
+        token, q = q.pop("page_token")
+        assert token == "abc"
+        assert q == httpx.QueryParams("size=10")
+        ```
+        """
+
+    def remove_value(self, key: str, value: PrimitiveData) -> QueryParams:
+        """
+        Return a new QueryParams instance, removing the first occurrence of a
+        key/value pair. The key is removed once it has no values left.
+
+        Usage:
+        ```
+        q = httpx.QueryParams("tag=a&tag=b&tag=a")
+        q = q.remove_value("tag", "a")
+        assert q == httpx.QueryParams("tag=b&tag=a")
+        ```
+        """
+
+    def sort(self) -> QueryParams:
+        """
+        Return a new QueryParams instance with the keys sorted by their encoded
+        form. Multiple values for the same key keep their relative order.
+
+        Usage:
+        ```
+        q = httpx.QueryParams("b=2&a=1&b=0")
+        assert str(q.sort()) == "a=1&b=2&b=0"
+        ```
+        """
+
     def merge(self, params: QueryParamTypes | None = None) -> QueryParams:
         """
         Return a new QueryParams instance, updated with.
@@ -142,18 +713,136 @@ class QueryParams(typing.Mapping[str, str]):
         ```
         """
 
+    def merge_append(self, params: QueryParamTypes | None = None) -> QueryParams:
+        """
+        Return a new QueryParams instance, updated with the given params. Unlike
+        `merge`, values for keys present on both sides are kept from both.
+
+        Usage:
+        ```
+        q = httpx.QueryParams("tag=a")
+        q = q.merge_append("tag=b&page=1")
+        assert q == httpx.QueryParams("tag=a&tag=b&page=1")
+        ```
+        """
+
+    def total(self) -> int:
+        """
+        Return the number of `(key, value)` pairs across all keys. Unlike
+        `len()`, repeated keys are counted once per value.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("a=1&a=2&b=3")
+        assert q.total() == 3
+        assert len(q) == 2
+        ```
+        """
+
+    def count(self, key: str) -> int:
+        """
+        Return the number of values for a given key, or `0` if it is missing.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("a=1&a=2&b=3")
+        assert q.count("a") == 2
+        assert q.count("c") == 0
+        ```
+        """
+
+    def __or__(self, other: QueryParamTypes) -> QueryParams: ...
+    def __ror__(self, other: QueryParamTypes) -> QueryParams: ...
     def __getitem__(self, key: typing.Any) -> str: ...
-    def __contains__(self, key: typing.Any) -> bool: ...
+    def __contains__(self, item: str | tuple[str, typing.Any]) -> bool: ...
     def __iter__(self) -> typing.Iterator[typing.Any]: ...
+    def __reversed__(self) -> typing.Iterator[str]: ...
     def __len__(self) -> int: ...
     def __bool__(self) -> bool: ...
     def __hash__(self) -> int: ...
     def __eq__(self, other: typing.Any) -> bool: ...
     def __str__(self) -> str: ...
+    def __bytes__(self) -> bytes: ...
+    def __format__(self, spec: str) -> str:
+        """
+        Render the query string with a format spec: an empty spec behaves like
+        `str()`, a single punctuation character is used as the pair separator,
+        and `pretty` puts each pair on its own line.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("a=1&b=2")
+        assert f"{q:;}" == "a=1;b=2"
+        assert f"{q:pretty}" == "a=1\nb=2"
+        ```
+        """
+
     def __repr__(self) -> str: ...
+    def literal(self) -> str:
+        """
+        Return a constructor call that evaluates back to these exact params,
+        duplicate keys included.
+
+        Usage:
+
+        ```
+        q = httpx.QueryParams("a=1&a=2")
+        assert q.literal() == "QueryParams([('a', '1'), ('a', '2')])"
+        ```
+        """
+
+    def __sizeof__(self) -> int: ...
+    def __copy__(self) -> QueryParams: ...
+    def __deepcopy__(self, memo: dict[int, typing.Any]) -> QueryParams: ...
+    def __reduce__(
+        self,
+    ) -> tuple[type[QueryParams], tuple[list[tuple[str, str]]]]: ...
     def update(self, params: QueryParamTypes | None = None) -> None: ...
     def __setitem__(self, key: str, value: str) -> None: ...
 
+class QueryParamsBuilder:
+    """
+    A mutable builder for assembling query parameters in place, without
+    copying on every change.
+
+    Usage:
+
+    ```
+    builder = httpx.QueryParams.builder()
+    for page in range(3):
+        builder.add("page", page)
+    builder["sort"] = "asc"
+    q = builder.build()
+    assert q == httpx.QueryParams("page=0&page=1&page=2&sort=asc")
+    ```
+    """
+
+    def __init__(self, params: QueryParamTypes | None = None) -> None: ...
+    def set(
+        self, key: str, value: typing.Any = None, *, bool_format: BoolFormat = "lower"
+    ) -> QueryParamsBuilder: ...
+    def add(
+        self, key: str, value: typing.Any = None, *, bool_format: BoolFormat = "lower"
+    ) -> QueryParamsBuilder: ...
+    def set_list(
+        self, key: str, values: typing.Sequence[PrimitiveData]
+    ) -> QueryParamsBuilder: ...
+    def remove(self, key: str) -> QueryParamsBuilder: ...
+    def update(self, params: QueryParamTypes) -> QueryParamsBuilder: ...
+    def build(self) -> QueryParams:
+        """
+        Return the accumulated parameters as an immutable QueryParams,
+        leaving the builder empty.
+        """
+
+    def __setitem__(self, key: str, value: typing.Any) -> None: ...
+    def __delitem__(self, key: str) -> None: ...
+    def __contains__(self, key: str) -> bool: ...
+    def __len__(self) -> int: ...
+
 def normalize_path(path: str) -> str:
     """
     Drop "." and ".." segments from a URL path.
@@ -163,20 +852,199 @@ def normalize_path(path: str) -> str:
         normalize_path("/path/./to/somewhere/..") == "/path/to"
     """
 
-def quote(string: str, safe: str) -> str:
+def quote(
+    string: str,
+    safe: str | bytes,
+    *,
+    treat_percent_as_literal: bool = False,
+    uppercase_escapes: bool = False,
+) -> str:
     """
     Use percent-encoding to quote a string, omitting existing '%xx' escape sequences.
 
     See: https://www.rfc-editor.org/rfc/rfc3986#section-2.1
 
     * `string`: The string to be percent-escaped.
-    * `safe`: A string containing characters that may be treated as safe, and do not
-        need to be escaped. Unreserved characters are always treated as safe.
+    * `safe`: ASCII characters, as `str` or `bytes`, that may be treated as safe,
+        and do not need to be escaped. Unreserved characters are always treated as
+        safe. Non-ASCII characters raise a `ValueError`.
         See: https://www.rfc-editor.org/rfc/rfc3986#section-2.3
+    * `treat_percent_as_literal`: Encode every '%' as '%25', including those that
+        begin a valid escape sequence and regardless of `safe`. By default a '%'
+        is only encoded when it doesn't begin an escape and '%' isn't in `safe`.
+    * `uppercase_escapes`: Rewrite existing escape sequences with uppercase hex
+        digits, so the result never mixes '%2f' and '%2F'. Off by default, since
+        WHATWG keeps existing escapes as they are.
+
+    Quoting is idempotent unless `treat_percent_as_literal` is set: for any
+    string and ASCII `safe`, `quote(quote(s, safe), safe) == quote(s, safe)`.
+    """
+
+def percent_encoded(string: str, safe: str | bytes = "") -> str:
+    """
+    Percent-encode every character outside the unreserved and `safe` sets.
+
+    Unlike `quote`, existing '%xx' sequences get no special treatment, so use this
+    when the input is known to contain no escapes, e.g. a literal filename.
+
+    Usage:
+
+    ```
+    assert percent_encoded("report%20final.pdf") == "report%2520final.pdf"
+    assert quote("report%20final.pdf", "") == "report%20final.pdf"
+    ```
+    """
+
+def quote_iter(
+    string: str,
+    safe: str | bytes,
+    chunk_size: int = 65536,
+    *,
+    treat_percent_as_literal: bool = False,
+    uppercase_escapes: bool = False,
+) -> typing.Iterator[str]:
+    """
+    Quote a string like `quote`, yielding the output in chunks so large inputs
+    can be written out incrementally.
+
+    Each chunk encodes roughly `chunk_size` characters of input. Chunks never
+    split a multi-byte character or an existing '%xx' escape sequence, so
+    joining them gives exactly `quote(string, safe)`.
+    """
+
+def quote_path(path: str) -> str:
+    """
+    Quote a URL path with the safe set httpx uses for paths. '/', ':', '@' and
+    the sub-delimiters are kept, while '?', '#' and spaces are escaped.
+
+    Usage:
+
+    ```
+    assert quote_path("/a b/c:d@e?f") == "/a%20b/c:d@e%3Ff"
+    ```
+    """
+
+def quote_query(query: str) -> str:
+    """
+    Quote a URL query with the safe set httpx uses for queries. Like
+    `quote_path`, but '?' is also kept. '#' is still escaped.
+
+    Usage:
+
+    ```
+    assert quote_query("a=1&b=/?x#y") == "a=1&b=/?x%23y"
+    ```
+    """
+
+def quote_fragment(fragment: str) -> str:
+    """
+    Quote a URL fragment with the safe set httpx uses for fragments, which keeps
+    '?', '/' and '#'.
+    """
+
+def quote_userinfo(userinfo: str, password: str | None = None) -> str:
+    """
+    Quote the userinfo component of a URL, escaping '@'.
+
+    With a single argument the userinfo is taken to be already joined, and ':'
+    is kept as the separator. Passing the username and password separately
+    quotes each one on its own, so a ':' inside either is escaped, and joins
+    them with ':'.
+
+    As with `quote`, a '%' is kept only when it begins an escape sequence, and
+    is otherwise escaped.
+
+    Usage:
+
+    ```
+    assert quote_userinfo("user:p@ss") == "user:p%40ss"
+    assert quote_userinfo("user", "p:ss@") == "user:p%3Ass%40"
+    assert quote_userinfo("user", "100%") == "user:100%25"
+    ```
+    """
+
+def urlencode(
+    query: typing.Mapping[str, typing.Any] | typing.Iterable[tuple[str, typing.Any]],
+    doseq: bool = True,
+    safe: str | bytes = "",
+    quote_via: typing.Literal["quote_plus", "quote"] = "quote_plus",
+) -> str:
+    """
+    Encode a mapping or a sequence of pairs as a query string, like
+    `urllib.parse.urlencode`, without building a `QueryParams`.
+
+    Pairs keep the order they are given in. With `doseq`, list, tuple and set
+    values are expanded into one pair per item. Values are coerced the same way
+    as `QueryParams` values, so `True` renders as `true` and `None` as an empty
+    string.
+
+    Usage:
+
+    ```
+    assert urlencode({"q": "a b", "tag": ["x", "y"]}) == "q=a+b&tag=x&tag=y"
+    ```
     """
 
-def unquote(value: str) -> str: ...
+def quote_from_bytes(data: bytes, safe: str | bytes = "/") -> str:
+    """
+    Percent-encode arbitrary bytes, which need not be valid UTF-8. Every byte
+    outside the unreserved and `safe` sets is escaped, '%' included. Matches
+    `urllib.parse.quote_from_bytes`.
+    """
+
+def quote_plus(string: str, safe: str | bytes = "") -> str:
+    """
+    Quote a string in the form style used by query strings: spaces become '+',
+    and every other character outside the unreserved and `safe` sets is
+    percent-encoded, including '+' and '%'. Matches `urllib.parse.quote_plus`.
+    """
+
+def unquote(
+    string: str,
+    encoding: str = "utf-8",
+    errors: typing.Literal["replace", "strict", "latin-1"] = "replace",
+) -> str:
+    """
+    Decode '%xx' escape sequences, reversing `quote`.
+
+    Malformed sequences such as a lone '%' are left untouched. The decoded bytes
+    are read in `encoding`, and `errors` picks how invalid byte runs are handled:
+
+    * `replace`: substitute U+FFFD.
+    * `strict`: raise `UnicodeDecodeError`.
+    * `latin-1`: decode each invalid byte as latin-1. Only valid with UTF-8.
+
+    Usage:
+
+    ```
+    assert unquote("caf%C3%A9") == "café"
+    assert unquote("caf%E9", encoding="latin-1") == "café"
+    ```
+    """
+
+def unquote_plus(
+    string: str,
+    encoding: str = "utf-8",
+    errors: typing.Literal["replace", "strict", "latin-1"] = "replace",
+) -> str:
+    """
+    Like `unquote`, but also decode '+' as a space, as used by form data and
+    query strings. An escaped '%2B' still decodes to '+'.
+    """
+
+def strip_quotes(value: str) -> str: ...
 def find_ascii_non_printable(s: str) -> typing.Optional[int]: ...
+def find_invalid_percent_encoding(s: str) -> typing.Optional[int]:
+    """
+    Return the index of the first '%' that doesn't begin a valid '%xx' escape
+    sequence, or `None` if there is none.
+    """
+
+def is_valid_percent_encoding(s: str) -> bool:
+    """
+    Return whether every '%' in the string begins a valid '%xx' escape sequence.
+    """
+
 def validate_path(path: str, has_scheme: bool, has_authority: bool) -> None:
     """
     Path validation rules that depend on if the URL contains
@@ -227,4 +1095,12 @@ class CookieConflict(Exception):
 
     def __init__(self, message: str) -> None: ...
 
+class QueryParamsTooLarge(Exception):
+    """
+    Query parameters exceeded the `max_params` or `max_length` limits
+    given when constructing `QueryParams`.
+    """
+
+    def __init__(self, message: str) -> None: ...
+
 def encode_host(host: str) -> str: ...
@@ -5,11 +5,11 @@ from urllib.parse import unquote
 
 import idna
 
-from ._httpx import QueryParams
+from ._httpx import QueryParams, QueryParamsBuilder
 from ._types import QueryParamTypes
 from ._urlparse import urlparse
 
-__all__ = ["URL", "QueryParams"]
+__all__ = ["URL", "QueryParams", "QueryParamsBuilder"]
 
 
 if not typing.TYPE_CHECKING:
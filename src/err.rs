@@ -41,3 +41,25 @@ impl From<CookieConflict> for PyErr {
         PyErr::new::<CookieConflict, _>(err.message)
     }
 }
+
+#[pyclass(extends=PyException, subclass)]
+pub struct QueryParamsTooLarge {
+    #[pyo3(get)]
+    message: String,
+}
+
+#[pymethods]
+impl QueryParamsTooLarge {
+    #[new]
+    pub fn new(message: &str) -> Self {
+        Self {
+            message: message.to_owned(),
+        }
+    }
+}
+
+impl From<QueryParamsTooLarge> for PyErr {
+    fn from(err: QueryParamsTooLarge) -> Self {
+        PyErr::new::<QueryParamsTooLarge, _>(err.message)
+    }
+}
@@ -1,5 +1,5 @@
 mod py_module;
-mod urls;
+pub mod urls;
 mod urlparse;
 mod models;
 mod err;
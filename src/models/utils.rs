@@ -1,7 +1,7 @@
 use pyo3::prelude::*;
 
 #[pyfunction]
-pub fn unquote(value: &str) -> String {
+pub fn strip_quotes(value: &str) -> String {
     if value.starts_with('"') && value.ends_with('"') {
         value[1..value.len() - 1].to_owned()
     } else if value.starts_with('\'') && value.ends_with('\'') {
@@ -4,9 +4,13 @@ use pyo3::prelude::*;
 mod _httpx {
     #[pymodule_export]
     use crate::{
-        err::{CookieConflict, InvalidUrl},
-        models::utils::unquote,
-        urlparse::{encode_host, find_ascii_non_printable, normalize_path, normalize_port, quote, validate_path},
-        urls::QueryParams,
+        err::{CookieConflict, InvalidUrl, QueryParamsTooLarge},
+        models::utils::strip_quotes,
+        urlparse::{
+            encode_host, find_ascii_non_printable, find_invalid_percent_encoding, is_valid_percent_encoding,
+            normalize_path, normalize_port, py_percent_encoded, py_quote, py_quote_from_bytes, py_quote_plus,
+            quote_fragment, quote_iter, quote_path, quote_query, quote_userinfo, unquote, unquote_plus, validate_path,
+        },
+        urls::{urlencode, QueryParams, QueryParamsBuilder},
     };
 }
@@ -1,7 +1,15 @@
-use std::net::{Ipv4Addr, Ipv6Addr};
+use std::{
+    borrow::Cow,
+    fmt::Write,
+    net::{Ipv4Addr, Ipv6Addr},
+};
 
 use num_bigint::BigInt;
-use pyo3::{prelude::*, types::PyString};
+use pyo3::{
+    exceptions::{PyUnicodeDecodeError, PyValueError},
+    prelude::*,
+    types::{PyBytes, PyString},
+};
 
 use crate::err::InvalidUrl;
 
@@ -31,70 +39,541 @@ pub fn normalize_path(path: &str) -> String {
 
 const UNRESERVED_CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-._~";
 
-pub fn percent_encoded(string: &str, safe: &str) -> String {
-    let safe = safe.as_bytes();
-    string
-        .bytes()
-        .map(|b| {
-            if UNRESERVED_CHARS.contains(&b) || safe.contains(&b) {
-                (b as char).to_string()
-            } else {
-                format!("%{:02X}", b)
-            }
-        })
-        .collect::<String>()
+const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+fn push_escape<W: Write>(out: &mut W, b: u8) -> std::fmt::Result {
+    out.write_char('%')?;
+    out.write_char(HEX[(b >> 4) as usize] as char)?;
+    out.write_char(HEX[(b & 0x0F) as usize] as char)
 }
 
-fn is_percent_encoded(s: &[u8]) -> bool {
-    s.len() == 3 && s[0] == b'%' && s[1].is_ascii_hexdigit() && s[2].is_ascii_hexdigit()
+/// The `safe` argument of the Python quoting functions, given as `str` or `bytes`.
+/// Only ASCII is accepted: safe characters are matched byte by byte, so a multi-byte
+/// character would leave parts of its UTF-8 sequence unescaped.
+#[derive(Debug, Clone, Default)]
+pub struct SafeChars(String);
+
+impl SafeChars {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<'py> FromPyObject<'py> for SafeChars {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(safe) = ob.downcast::<PyBytes>() {
+            return match safe.as_bytes().iter().find(|b| !b.is_ascii()) {
+                Some(b) => Err(PyValueError::new_err(format!(
+                    "safe must only contain ASCII characters, got byte 0x{:02X}.",
+                    b
+                ))),
+                None => Ok(SafeChars(safe.as_bytes().iter().map(|&b| b as char).collect())),
+            };
+        }
+
+        let safe = ob.extract::<String>()?;
+        match safe.chars().find(|c| !c.is_ascii()) {
+            Some(c) => Err(PyValueError::new_err(format!(
+                "safe must only contain ASCII characters, got '{}'.",
+                c
+            ))),
+            None => Ok(SafeChars(safe)),
+        }
+    }
+}
+
+/// Hand back the caller's own string object when nothing needed encoding.
+fn same_or_new<'py>(string: Bound<'py, PyString>, encoded: Cow<'_, str>) -> Bound<'py, PyString> {
+    match encoded {
+        Cow::Borrowed(_) => string,
+        Cow::Owned(encoded) => PyString::new(string.py(), &encoded),
+    }
 }
 
 #[pyfunction]
-pub fn quote(string: &str, safe: &str) -> String {
+#[pyo3(name = "percent_encoded", signature = (string, safe = SafeChars::default()))]
+pub fn py_percent_encoded(string: Bound<'_, PyString>, safe: SafeChars) -> PyResult<Bound<'_, PyString>> {
+    let encoded = percent_encoded(string.to_str()?, &safe.0);
+    Ok(same_or_new(string.clone(), encoded))
+}
+
+#[pyfunction]
+#[pyo3(name = "quote_from_bytes", signature = (data, safe = SafeChars("/".to_owned())))]
+pub fn py_quote_from_bytes(data: &[u8], safe: SafeChars) -> String {
+    quote_from_bytes(data, &safe.0)
+}
+
+#[pyfunction]
+#[pyo3(
+    name = "quote",
+    signature = (string, safe, *, treat_percent_as_literal = false, uppercase_escapes = false)
+)]
+pub fn py_quote(
+    string: Bound<'_, PyString>,
+    safe: SafeChars,
+    treat_percent_as_literal: bool,
+    uppercase_escapes: bool,
+) -> PyResult<Bound<'_, PyString>> {
+    let options = QuoteOptions {
+        treat_percent_as_literal,
+        uppercase_escapes,
+    };
+    let encoded = quote(string.to_str()?, &safe.0, options);
+    Ok(same_or_new(string.clone(), encoded))
+}
+
+/// Quote a large string a chunk at a time, so the whole encoded output never has to
+/// be held in memory at once.
+#[pyfunction]
+#[pyo3(
+    signature = (string, safe, chunk_size = 65536, *, treat_percent_as_literal = false, uppercase_escapes = false)
+)]
+pub fn quote_iter(
+    string: Bound<'_, PyString>,
+    safe: SafeChars,
+    chunk_size: usize,
+    treat_percent_as_literal: bool,
+    uppercase_escapes: bool,
+) -> PyResult<QuoteIterator> {
+    if chunk_size == 0 {
+        return Err(PyValueError::new_err("chunk_size must be greater than zero."));
+    }
+    Ok(QuoteIterator {
+        string: string.unbind(),
+        safe: safe.0,
+        chunk_size,
+        options: QuoteOptions {
+            treat_percent_as_literal,
+            uppercase_escapes,
+        },
+        position: 0,
+    })
+}
+
+#[pyclass]
+#[derive(Debug)]
+pub struct QuoteIterator {
+    string: Py<PyString>,
+    safe: String,
+    chunk_size: usize,
+    options: QuoteOptions,
+    position: usize,
+}
+
+#[pymethods]
+impl QuoteIterator {
+    pub fn __iter__(slf: PyRefMut<Self>) -> PyRefMut<Self> {
+        slf
+    }
+
+    pub fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<String>> {
+        let string = self.string.bind(py).to_str()?;
+        if self.position >= string.len() {
+            return Ok(None);
+        }
+        let start = self.position;
+        let end = chunk_end(string, start, self.chunk_size);
+        self.position = end;
+
+        let mut chunk = String::with_capacity(end - start);
+        quote_into(&mut chunk, &string[start..end], &self.safe, self.options).expect("writing to a String cannot fail");
+        Ok(Some(chunk))
+    }
+}
+
+/// Where the chunk starting at `start` should end: at most `chunk_size` bytes on, but
+/// never inside a character or a `%XX` escape, since either would quote differently
+/// once split. A chunk always holds at least one of them, however small `chunk_size` is.
+fn chunk_end(string: &str, start: usize, chunk_size: usize) -> usize {
     let s = string.as_bytes();
-    let mut result = String::with_capacity(s.len());
+    let escape_at = |i: usize| s[i] == b'%' && i + 2 < s.len() && is_percent_encoded(&s[i..i + 3]);
+
+    let mut end = start.saturating_add(chunk_size).min(s.len());
+    if end == s.len() {
+        return end;
+    }
+    if let Some(i) = (end.saturating_sub(2).max(start)..end).find(|&i| escape_at(i)) {
+        end = i;
+    }
+    while !string.is_char_boundary(end) {
+        end -= 1;
+    }
+    if end > start {
+        return end;
+    }
+
+    if escape_at(start) {
+        return start + 3;
+    }
+    end = start + 1;
+    while !string.is_char_boundary(end) {
+        end += 1;
+    }
+    end
+}
+
+/// The characters beyond the unreserved set that each URL component keeps unescaped.
+/// These match the WHATWG percent-encode sets used by `httpx._urlparse`.
+const PATH_SAFE: &str = "!$%&'()*+,/:;=@[\\]^|";
+const QUERY_SAFE: &str = "!$%&'()*+,/:;=?@[\\]^`{|}";
+const FRAGMENT_SAFE: &str = "!#$%&'()*+,/:;=?@[\\]^{|}";
+/// A username or password on its own, where `:` must be escaped. Unlike the sets
+/// above, `%` is left out, so a `%` that doesn't begin an escape is encoded.
+const USERINFO_PART_SAFE: &str = "!$&'()*+,";
+/// A joined `user:password`, where `:` is the separator.
+const USERINFO_SAFE: &str = "!$&'()*+,:";
+
+#[pyfunction]
+pub fn quote_path(path: Bound<'_, PyString>) -> PyResult<Bound<'_, PyString>> {
+    let encoded = quote(path.to_str()?, PATH_SAFE, QuoteOptions::default());
+    Ok(same_or_new(path.clone(), encoded))
+}
+
+#[pyfunction]
+pub fn quote_query(query: Bound<'_, PyString>) -> PyResult<Bound<'_, PyString>> {
+    let encoded = quote(query.to_str()?, QUERY_SAFE, QuoteOptions::default());
+    Ok(same_or_new(query.clone(), encoded))
+}
+
+#[pyfunction]
+pub fn quote_fragment(fragment: Bound<'_, PyString>) -> PyResult<Bound<'_, PyString>> {
+    let encoded = quote(fragment.to_str()?, FRAGMENT_SAFE, QuoteOptions::default());
+    Ok(same_or_new(fragment.clone(), encoded))
+}
+
+/// Quote the userinfo of a URL. Given just `userinfo`, it is taken to already be
+/// joined, so a `:` is kept as the separator. Given a `password` as well, each part
+/// is quoted on its own, escaping any `:` in them, and then joined with `:`.
+#[pyfunction]
+#[pyo3(signature = (userinfo, password = None))]
+pub fn quote_userinfo(userinfo: &str, password: Option<&str>) -> String {
+    match password {
+        None => quote(userinfo, USERINFO_SAFE, QuoteOptions::default()).into_owned(),
+        Some(password) => format!(
+            "{}:{}",
+            quote(userinfo, USERINFO_PART_SAFE, QuoteOptions::default()),
+            quote(password, USERINFO_PART_SAFE, QuoteOptions::default())
+        ),
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "quote_plus", signature = (string, safe = SafeChars::default()))]
+pub fn py_quote_plus(string: &str, safe: SafeChars) -> String {
+    quote_plus(string, &safe.0)
+}
+
+fn is_safe(b: u8, safe: &[u8]) -> bool {
+    UNRESERVED_CHARS.contains(&b) || safe.contains(&b)
+}
+
+/// Percent-encode everything outside the unreserved and `safe` sets. Unlike `quote`,
+/// text that already looks like an escape is encoded too. Borrows the input when
+/// there is nothing to encode, which is the common case for path segments and hosts.
+pub fn percent_encoded<'a>(string: &'a str, safe: &str) -> Cow<'a, str> {
+    if string.bytes().all(|b| is_safe(b, safe.as_bytes())) {
+        Cow::Borrowed(string)
+    } else {
+        Cow::Owned(quote_from_bytes(string.as_bytes(), safe))
+    }
+}
+
+/// Percent-encode arbitrary bytes, with no assumption that they are UTF-8.
+pub fn quote_from_bytes(data: &[u8], safe: &str) -> String {
+    let mut result = String::with_capacity(data.len());
+    percent_encoded_into(&mut result, data, safe).expect("writing to a String cannot fail");
+    result
+}
+
+fn percent_encoded_into<W: Write>(out: &mut W, data: &[u8], safe: &str) -> std::fmt::Result {
+    let safe = safe.as_bytes();
+    for &b in data {
+        if is_safe(b, safe) {
+            out.write_char(b as char)?;
+        } else {
+            push_escape(out, b)?;
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn is_percent_encoded(s: &[u8]) -> bool {
+    s.len() == 3 && s[0] == b'%' && s[1].is_ascii_hexdigit() && s[2].is_ascii_hexdigit()
+}
+
+/// How `quote` treats `%` and the escapes already in its input.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct QuoteOptions {
+    /// Encode every `%`, whatever follows it and whatever `safe` says.
+    pub treat_percent_as_literal: bool,
+    /// Rewrite kept escapes with uppercase hex digits, like the ones `quote` emits.
+    /// Off by default, since WHATWG keeps existing escapes as they are.
+    pub uppercase_escapes: bool,
+}
+
+/// A `%` that begins a valid `%XX` escape is kept, and any other `%` is encoded to
+/// `%25` unless `safe` contains it. See `QuoteOptions` for the alternatives.
+///
+/// Without `treat_percent_as_literal`, quoting is idempotent for any ASCII `safe`:
+/// the output holds only safe characters and escapes, and a `%` let through by
+/// `safe` can't be followed by two hex digits that weren't already an escape,
+/// since anything encoded after it starts with `%`.
+///
+/// The input is borrowed when quoting would leave it unchanged.
+pub fn quote<'a>(string: &'a str, safe: &str, options: QuoteOptions) -> Cow<'a, str> {
+    if is_quoted(string, safe, options) {
+        return Cow::Borrowed(string);
+    }
+    let mut result = String::with_capacity(string.len());
+    quote_into(&mut result, string, safe, options).expect("writing to a String cannot fail");
+    Cow::Owned(result)
+}
 
+/// Whether `quote` would return `string` as it is: every byte is safe, and with
+/// `uppercase_escapes` every escape it keeps is already in uppercase hex.
+fn is_quoted(string: &str, safe: &str, options: QuoteOptions) -> bool {
+    let (s, safe) = (string.as_bytes(), safe.as_bytes());
+    let mut i = 0;
+    while i < s.len() {
+        if s[i] == b'%' {
+            if options.treat_percent_as_literal {
+                return false;
+            }
+            if i + 2 < s.len() && is_percent_encoded(&s[i..i + 3]) {
+                if options.uppercase_escapes && (s[i + 1].is_ascii_lowercase() || s[i + 2].is_ascii_lowercase()) {
+                    return false;
+                }
+                i += 3;
+                continue;
+            }
+        }
+        if !is_safe(s[i], safe) {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// `quote`, writing the output to `out` as it goes.
+pub fn quote_into<W: Write>(out: &mut W, string: &str, safe: &str, options: QuoteOptions) -> std::fmt::Result {
+    if options.treat_percent_as_literal {
+        return percent_encoded_into(out, string.as_bytes(), &safe.replace('%', ""));
+    }
+
+    let s = string.as_bytes();
     let mut start = 0;
     let mut i = 0;
     while i < s.len() {
         if s[i] == b'%' && i + 2 < s.len() && is_percent_encoded(&s[i..i + 3]) {
-            if start < i {
-                result.push_str(&percent_encoded(&string[start..i], safe));
+            percent_encoded_into(out, &s[start..i], safe)?;
+            if options.uppercase_escapes {
+                out.write_char('%')?;
+                out.write_char(s[i + 1].to_ascii_uppercase() as char)?;
+                out.write_char(s[i + 2].to_ascii_uppercase() as char)?;
+            } else {
+                out.write_str(&string[i..i + 3])?;
             }
-            result.push_str(&string[i..i + 3]);
             i += 3;
             start = i;
         } else {
             i += 1;
         }
     }
+    percent_encoded_into(out, &s[start..], safe)
+}
+
+/// How byte runs that are not valid UTF-8 are handled by `unquote`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DecodeErrors {
+    /// Substitute U+FFFD for each invalid run.
+    #[default]
+    Replace,
+    /// Raise `UnicodeDecodeError`.
+    Strict,
+    /// Decode each invalid byte as the latin-1 character of the same value.
+    Latin1,
+}
+
+impl<'py> FromPyObject<'py> for DecodeErrors {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        match ob.extract::<String>()?.as_str() {
+            "replace" => Ok(DecodeErrors::Replace),
+            "strict" => Ok(DecodeErrors::Strict),
+            "latin-1" => Ok(DecodeErrors::Latin1),
+            other => Err(PyValueError::new_err(format!(
+                "errors must be one of 'replace', 'strict' or 'latin-1', got '{}'.",
+                other
+            ))),
+        }
+    }
+}
+
+/// Decode `%XX` escapes into raw bytes. Anything that isn't a complete escape,
+/// such as a lone `%` or `%Z1`, is kept as-is.
+pub(crate) fn percent_decode(string: &str) -> Cow<'_, [u8]> {
+    let s = string.as_bytes();
+    if !s.contains(&b'%') {
+        return Cow::Borrowed(s);
+    }
+
+    let mut decoded = Vec::with_capacity(s.len());
+    let mut i = 0;
+    while i < s.len() {
+        if s[i] == b'%' && i + 2 < s.len() && is_percent_encoded(&s[i..i + 3]) {
+            // Both digits are ASCII hex, so the slice is on char boundaries and parses.
+            decoded.push(u8::from_str_radix(&string[i + 1..i + 3], 16).unwrap());
+            i += 3;
+        } else {
+            decoded.push(s[i]);
+            i += 1;
+        }
+    }
+    Cow::Owned(decoded)
+}
+
+fn decode_utf8(py: Python<'_>, bytes: &[u8], errors: DecodeErrors) -> PyResult<String> {
+    match errors {
+        DecodeErrors::Replace => Ok(String::from_utf8_lossy(bytes).into_owned()),
+        DecodeErrors::Strict => std::str::from_utf8(bytes)
+            .map(str::to_owned)
+            .map_err(|err| match PyUnicodeDecodeError::new_utf8(py, bytes, err) {
+                Ok(exc) => PyErr::from_value(exc.into_any()),
+                Err(err) => err,
+            }),
+        DecodeErrors::Latin1 => {
+            let mut decoded = String::with_capacity(bytes.len());
+            for chunk in bytes.utf8_chunks() {
+                decoded.push_str(chunk.valid());
+                decoded.extend(chunk.invalid().iter().map(|&b| b as char));
+            }
+            Ok(decoded)
+        }
+    }
+}
+
+/// Decode bytes in any codec Python knows. UTF-8 and latin-1 are handled here, and
+/// anything else is left to `bytes.decode`, where the `latin-1` policy has no meaning.
+fn decode(py: Python<'_>, bytes: &[u8], encoding: &str, errors: DecodeErrors) -> PyResult<String> {
+    let codec: Cow<'_, str> = match encoding {
+        "utf-8" | "utf8" | "UTF-8" => Cow::Borrowed("utf-8"),
+        _ => Cow::Owned(
+            PyModule::import(py, "codecs")?
+                .call_method1("lookup", (encoding,))?
+                .getattr("name")?
+                .extract()?,
+        ),
+    };
+    match &*codec {
+        "utf-8" => decode_utf8(py, bytes, errors),
+        "iso8859-1" => Ok(bytes.iter().map(|&b| b as char).collect()),
+        _ => {
+            let errors = match errors {
+                DecodeErrors::Replace => "replace",
+                DecodeErrors::Strict => "strict",
+                DecodeErrors::Latin1 => {
+                    return Err(PyValueError::new_err(format!(
+                        "errors='latin-1' is only supported with the UTF-8 encoding, got '{}'.",
+                        encoding
+                    )))
+                }
+            };
+            PyBytes::new(py, bytes)
+                .call_method1("decode", (encoding, errors))?
+                .extract()
+        }
+    }
+}
 
-    if start < s.len() {
-        result.push_str(&percent_encoded(&string[start..], safe));
+/// Reverse `quote`. Escapes are decoded to raw bytes first, so a character spread
+/// over several escapes such as `%C3%A9` decodes as a whole.
+#[pyfunction]
+#[pyo3(signature = (string, encoding = "utf-8", errors = DecodeErrors::Replace))]
+pub fn unquote(py: Python<'_>, string: &str, encoding: &str, errors: DecodeErrors) -> PyResult<String> {
+    match percent_decode(string) {
+        Cow::Borrowed(_) => Ok(string.to_owned()),
+        Cow::Owned(bytes) => decode(py, &bytes, encoding, errors),
     }
+}
 
+fn plus_to_space(string: &str) -> Cow<'_, str> {
+    if string.contains('+') {
+        Cow::Owned(string.replace('+', " "))
+    } else {
+        Cow::Borrowed(string)
+    }
+}
+
+/// Form-style decoding: `+` becomes a space before `%XX` escapes are decoded, so an
+/// escaped `%2B` still comes out as `+`.
+#[pyfunction]
+#[pyo3(signature = (string, encoding = "utf-8", errors = DecodeErrors::Replace))]
+pub fn unquote_plus(py: Python<'_>, string: &str, encoding: &str, errors: DecodeErrors) -> PyResult<String> {
+    unquote(py, &plus_to_space(string), encoding, errors)
+}
+
+/// `unquote_plus` with invalid UTF-8 replaced, for callers that can't raise or
+/// don't hold the GIL.
+pub(crate) fn unquote_plus_lossy(string: &str) -> String {
+    let string = plus_to_space(string);
+    match percent_decode(&string) {
+        Cow::Borrowed(_) => string.into_owned(),
+        Cow::Owned(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+    }
+}
+
+/// Form-style quoting, as used for query strings: a space becomes `+`, and everything
+/// else outside the unreserved and `safe` sets is percent-encoded, `+` and `%` included.
+pub fn quote_plus(string: &str, safe: &str) -> String {
+    let mut result = String::with_capacity(string.len());
+    quote_plus_into(&mut result, string, safe);
     result
 }
 
+pub(crate) fn quote_plus_into(out: &mut String, string: &str, safe: &str) {
+    let safe = safe.as_bytes();
+    for b in string.bytes() {
+        match b {
+            b' ' => out.push('+'),
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => out.push(b as char),
+            _ if safe.contains(&b) => out.push(b as char),
+            _ => push_escape(out, b).expect("writing to a String cannot fail"),
+        }
+    }
+}
+
 #[pyfunction]
 pub fn find_ascii_non_printable(s: &str) -> Option<usize> {
     s.chars()
         .position(|c| c.is_ascii() && !c.is_ascii_graphic() && c != ' ')
 }
 
+/// The character position of the first `%` that doesn't begin a `%XX` escape.
+#[pyfunction]
+pub fn find_invalid_percent_encoding(s: &str) -> Option<usize> {
+    let bytes = s.as_bytes();
+    s.char_indices()
+        .position(|(i, c)| c == '%' && !(i + 2 < bytes.len() && is_percent_encoded(&bytes[i..i + 3])))
+}
+
+#[pyfunction]
+pub fn is_valid_percent_encoding(s: &str) -> bool {
+    find_invalid_percent_encoding(s).is_none()
+}
+
 pub(crate) trait PercentEncoded {
     fn percent_encoded(&self, safe: &str) -> String;
 }
 
 impl PercentEncoded for String {
     fn percent_encoded(&self, safe: &str) -> String {
-        quote(self, safe)
+        quote(self, safe, QuoteOptions::default()).into_owned()
     }
 }
 
 impl PercentEncoded for &str {
     fn percent_encoded(&self, safe: &str) -> String {
-        quote(self, safe)
+        quote(self, safe, QuoteOptions::default()).into_owned()
     }
 }
 
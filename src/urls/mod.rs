@@ -1,3 +1,13 @@
+//! Query parameters, usable from Rust without a Python interpreter.
+//!
+//! ```
+//! use httpx::urls::QueryParams;
+//!
+//! let q = QueryParams::from_pairs([("a", "1"), ("b", "2"), ("a", "3")].map(|(k, v)| (k.to_owned(), v.to_owned())));
+//! assert_eq!(q.multi_pairs().collect::<Vec<_>>(), [("a", "1"), ("a", "3"), ("b", "2")]);
+//! assert_eq!(q.to_query_string(), "a=1&a=3&b=2");
+//! ```
+
 mod query_params;
 
-pub use query_params::QueryParams;
+pub use query_params::{urlencode, QueryParams, QueryParamsBuilder, QueryParamsMultiPairs};
@@ -1,47 +1,341 @@
 use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
     fmt::Debug,
-    hash::{Hash, Hasher},
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::{Arc, OnceLock},
     vec::IntoIter,
 };
 
 use indexmap::IndexMap;
 use pyo3::{
-    exceptions::{PyAssertionError, PyKeyError, PyRuntimeError},
+    exceptions::{
+        PyAssertionError, PyIndexError, PyKeyError, PyRuntimeError, PyTypeError, PyUnicodeDecodeError, PyValueError,
+    },
     prelude::*,
-    types::{PyDict, PyList, PyTuple},
+    sync::GILOnceCell,
+    types::{
+        PyBool, PyBytes, PyDate, PyDict, PyFloat, PyFrozenSet, PyInt, PyList, PySequence, PySet, PyString, PyTime,
+        PyTuple, PyType,
+    },
     IntoPyObjectExt,
 };
+use smallvec::{smallvec, SmallVec};
+
+use crate::{
+    err::{InvalidUrl, QueryParamsTooLarge},
+    urlparse::{
+        percent_encoded, quote_plus, quote_plus_into, unquote_plus, unquote_plus_lossy, DecodeErrors, SafeChars,
+    },
+};
 
 trait ToQueryParamValue {
-    fn to_query_param_value(&self) -> PyResult<String>;
+    fn to_query_param_key(&self, options: ParseOptions) -> PyResult<String>;
+
+    fn to_query_param_value(&self, key: &str, options: ParseOptions) -> PyResult<String>;
+
+    fn to_query_param_values(&self, key: &str, options: ParseOptions) -> PyResult<Values>;
+}
+
+/// How boolean values are rendered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BoolFormat {
+    /// `true` / `false`
+    #[default]
+    Lower,
+    /// `True` / `False`
+    Python,
+    /// `1` / `0`
+    Int,
+}
+
+impl BoolFormat {
+    fn format(self, value: bool) -> &'static str {
+        match (self, value) {
+            (BoolFormat::Lower, true) => "true",
+            (BoolFormat::Lower, false) => "false",
+            (BoolFormat::Python, true) => "True",
+            (BoolFormat::Python, false) => "False",
+            (BoolFormat::Int, true) => "1",
+            (BoolFormat::Int, false) => "0",
+        }
+    }
+}
+
+impl<'py> FromPyObject<'py> for BoolFormat {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        match ob.extract::<String>()?.as_str() {
+            "lower" => Ok(BoolFormat::Lower),
+            "python" => Ok(BoolFormat::Python),
+            "int" => Ok(BoolFormat::Int),
+            other => Err(PyValueError::new_err(format!(
+                "bool_format must be one of 'lower', 'python' or 'int', got '{}'.",
+                other
+            ))),
+        }
+    }
+}
+
+/// An optional argument that tells an explicit `None` apart from no argument at all,
+/// which `Option` can't, since PyO3 extracts `None` as `Option::None`.
+#[derive(Debug, Default)]
+pub struct OptionalArg(Option<Py<PyAny>>);
+
+impl<'py> FromPyObject<'py> for OptionalArg {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        Ok(OptionalArg(Some(ob.clone().unbind())))
+    }
+}
+
+/// Options controlling how constructor input is parsed and coerced into parameter values.
+#[derive(Debug, Clone, Copy, Default)]
+struct ParseOptions {
+    drop_none: bool,
+    bool_format: BoolFormat,
+    max_params: Option<usize>,
+    max_length: Option<usize>,
+}
+
+impl ParseOptions {
+    fn check_length(&self, length: usize) -> PyResult<()> {
+        match self.max_length {
+            Some(max_length) if length > max_length => Err(QueryParamsTooLarge::new(&format!(
+                "Query string length {} exceeds the maximum of {}.",
+                length, max_length
+            ))
+            .into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_params(&self, count: usize) -> PyResult<()> {
+        match self.max_params {
+            Some(max_params) if count > max_params => Err(QueryParamsTooLarge::new(&format!(
+                "Number of query parameters exceeds the maximum of {}.",
+                max_params
+            ))
+            .into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// How many keys to reserve room for, given `count` separators in the input. Never
+    /// more than `max_params`, so an oversized input fails before allocating for it.
+    fn capacity(&self, count: usize) -> usize {
+        let capacity = count.saturating_add(1);
+        self.max_params
+            .map_or(capacity, |max_params| capacity.min(max_params))
+    }
 }
 
+static ENUM_TYPE: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+static DECIMAL_TYPE: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+static REAL_TYPE: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+
 impl ToQueryParamValue for Bound<'_, PyAny> {
-    fn to_query_param_value(&self) -> PyResult<String> {
+    /// Keys may be `str` or `bytes`. `int` and `bool` keys are coerced the same way
+    /// values are, matching `urlencode`, so `bool_format` applies to both. Anything
+    /// else is a `TypeError`.
+    fn to_query_param_key(&self, options: ParseOptions) -> PyResult<String> {
+        if let Ok(key) = self.downcast::<PyString>() {
+            key.extract()
+        } else if let Ok(key) = self.downcast::<PyBytes>() {
+            Ok(decode_utf8(self.py(), key.as_bytes())?.to_owned())
+        } else if let Ok(key) = self.extract::<bool>() {
+            Ok(options.bool_format.format(key).to_owned())
+        } else if self.is_instance_of::<PyInt>() {
+            self.str()?.extract()
+        } else {
+            Err(PyTypeError::new_err(format!(
+                "QueryParams keys must be str, got {}: {}",
+                self.get_type().name()?,
+                self.repr()?
+            )))
+        }
+    }
+
+    /// Supported values are `str`, `bytes` (decoded as UTF-8), `int`, `float`, `bool`,
+    /// `None`, `decimal.Decimal` and other `numbers.Real` types such as
+    /// `fractions.Fraction` (rendered with `str()`, so `Fraction(1, 3)` becomes `1/3`),
+    /// plus `datetime.datetime`, `datetime.date` and `datetime.time` (rendered with
+    /// `isoformat()`). `enum.Enum` members are rendered from their `.value`. Anything
+    /// else, including nested containers, is a `TypeError` naming the offending key.
+    fn to_query_param_value(&self, key: &str, options: ParseOptions) -> PyResult<String> {
+        // Fast paths for the common exact types, which can't be `Enum` members and
+        // so skip the `isinstance` checks below.
+        if let Ok(value) = self.downcast_exact::<PyString>() {
+            return Ok(value.to_str()?.to_owned());
+        } else if let Ok(value) = self.downcast_exact::<PyBool>() {
+            return Ok(options.bool_format.format(value.is_true()).to_owned());
+        } else if let Ok(value) = self.downcast_exact::<PyInt>() {
+            if let Ok(value) = value.extract::<i64>() {
+                return Ok(value.to_string());
+            }
+        }
+
+        let py = self.py();
         if self.is_none() {
             Ok("".to_owned())
+        } else if self.is_instance(ENUM_TYPE.import(py, "enum", "Enum")?)? {
+            // Checked first, since `IntEnum` and `StrEnum` members are also ints and strs.
+            self.getattr("value")?.to_query_param_value(key, options)
+        } else if let Ok(value) = self.downcast::<PyString>() {
+            value.extract()
+        } else if let Ok(value) = self.downcast::<PyBytes>() {
+            Ok(decode_utf8(py, value.as_bytes())?.to_owned())
         } else if let Ok(value) = self.extract::<bool>() {
-            Ok(value.to_string())
+            Ok(options.bool_format.format(value).to_owned())
+        } else if self.is_instance_of::<PyInt>() || self.is_instance_of::<PyFloat>() {
+            // Defer to Python's own formatting, so `1e+20`, `-0.0` and arbitrarily
+            // large ints render exactly as `str()` would.
+            self.str()?.extract()
+        } else if self.is_instance_of::<PyDate>() || self.is_instance_of::<PyTime>() {
+            // `str()` separates date and time with a space, which APIs rarely accept.
+            self.call_method0("isoformat")?.extract()
+        } else if self.is_instance(DECIMAL_TYPE.import(py, "decimal", "Decimal")?)?
+            || self.is_instance(REAL_TYPE.import(py, "numbers", "Real")?)?
+        {
+            self.str()?.extract()
+        } else if self.is_instance_of::<PyList>()
+            || self.is_instance_of::<PyTuple>()
+            || self.is_instance_of::<PyDict>()
+            || self.is_instance_of::<PySet>()
+            || self.is_instance_of::<PyFrozenSet>()
+        {
+            Err(PyTypeError::new_err(format!(
+                "Query parameter '{}' has a nested {} value, which is not supported.",
+                key,
+                self.get_type().name()?
+            )))
+        } else {
+            Err(PyTypeError::new_err(format!(
+                "Query parameter '{}' has an unsupported {} value. Expected str, int, float, bool or None.",
+                key,
+                self.get_type().name()?
+            )))
+        }
+    }
+
+    /// Expand list, tuple and set values into multiple values. When `drop_none` is set,
+    /// `None` items are omitted instead of being rendered as empty strings.
+    fn to_query_param_values(&self, key: &str, options: ParseOptions) -> PyResult<Values> {
+        if let Ok(value) = self.downcast::<PyList>() {
+            let mut values = SmallVec::with_capacity(value.len());
+            for item in value {
+                if !(options.drop_none && item.is_none()) {
+                    values.push(item.to_query_param_value(key, options)?.into());
+                }
+            }
+            Ok(values)
+        } else if let Ok(value) = self.downcast::<PyTuple>() {
+            let mut values = SmallVec::with_capacity(value.len());
+            for item in value {
+                if !(options.drop_none && item.is_none()) {
+                    values.push(item.to_query_param_value(key, options)?.into());
+                }
+            }
+            Ok(values)
+        } else if self.is_instance_of::<PySet>() || self.is_instance_of::<PyFrozenSet>() {
+            // Sets are unordered, so sort to keep the rendered query string deterministic.
+            let mut values = SmallVec::with_capacity(self.len()?);
+            for item in self.try_iter()? {
+                let item = item?;
+                if !(options.drop_none && item.is_none()) {
+                    values.push(item.to_query_param_value(key, options)?.into());
+                }
+            }
+            values.sort();
+            Ok(values)
+        } else if options.drop_none && self.is_none() {
+            Ok(SmallVec::new())
         } else {
-            self.str().and_then(|s| s.extract())
+            Ok(smallvec![self.to_query_param_value(key, options)?.into()])
         }
     }
 }
 
-fn urlencode(s: &str) -> String {
-    s.bytes()
-        .map(|b| match b {
-            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
-            b' ' => "+".to_string(),
-            _ => format!("%{:02X}", b),
-        })
-        .collect()
+/// A query string whose first key contains `://` is almost certainly a full URL
+/// that was passed by mistake, e.g. `https://example.com/path?a=1`.
+fn looks_like_url(s: &str, separator: &str) -> bool {
+    let first_pair = s.split(separator).next().unwrap_or_default();
+    let first_key = first_pair.split('=').next().unwrap_or_default();
+    first_key.contains("://")
 }
 
-#[pyclass(eq, frozen, str, hash)]
-#[derive(Debug, Clone)]
+/// Decode UTF-8 bytes, raising a `UnicodeDecodeError` that points at the offending byte.
+fn decode_utf8<'a>(py: Python<'_>, bytes: &'a [u8]) -> PyResult<&'a str> {
+    std::str::from_utf8(bytes).map_err(|err| match PyUnicodeDecodeError::new_utf8(py, bytes, err) {
+        Ok(exc) => PyErr::from_value(exc.into_any()),
+        Err(err) => err,
+    })
+}
+
+/// Keys used by most paginated and filtered APIs. Each is allocated once and shared
+/// by every QueryParams that uses it.
+const COMMON_KEYS: &[&str] = &[
+    "page",
+    "per_page",
+    "page_size",
+    "limit",
+    "offset",
+    "cursor",
+    "sort",
+    "order",
+    "q",
+    "query",
+    "filter",
+    "fields",
+    "include",
+    "search",
+    "id",
+    "type",
+    "format",
+    "lang",
+];
+
+static INTERNED_KEYS: OnceLock<HashMap<&'static str, Arc<str>>> = OnceLock::new();
+
+fn intern_key(key: &str) -> Arc<str> {
+    let interned = INTERNED_KEYS.get_or_init(|| {
+        COMMON_KEYS
+            .iter()
+            .map(|&key| (key, Arc::from(key)))
+            .collect()
+    });
+    match interned.get(key) {
+        Some(key) => key.clone(),
+        None => Arc::from(key),
+    }
+}
+
+/// Query strings at least this long are parsed with the GIL released. Below that,
+/// releasing and reacquiring it costs more than the parse itself.
+const ALLOW_THREADS_MIN_LENGTH: usize = 16 * 1024;
+
+/// Likewise for rendering, measured in distinct keys.
+const ALLOW_THREADS_MIN_KEYS: usize = 512;
+
+/// Keys and values are reference counted, so deriving a modified copy only bumps
+/// counts instead of duplicating every string. Most keys have a single value, which
+/// is stored inline.
+type Values = SmallVec<[Arc<str>; 1]>;
+type Params = IndexMap<Arc<str>, Values>;
+
+#[pyclass(frozen, module = "httpx._httpx")]
+#[derive(Debug)]
 pub struct QueryParams {
-    params: IndexMap<String, Vec<String>>,
+    /// Shared between copies until one of them is modified.
+    params: Arc<Params>,
+    /// The rendered query string and hash, computed on first use. Never carried
+    /// over by `clone()`, since clones are how modified copies are derived.
+    rendered: OnceLock<String>,
+    hash: OnceLock<u64>,
+}
+
+impl Clone for QueryParams {
+    fn clone(&self) -> Self {
+        QueryParams::from_shared(self.params.clone())
+    }
 }
 
 #[pymethods]
@@ -49,32 +343,252 @@ impl QueryParams {
     #[new]
     #[pyo3(signature = (*args, **kwargs))]
     pub fn new(args: &Bound<'_, PyTuple>, kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let options = ParseOptions::default();
         if args.len() > 1 {
             return Err(PyAssertionError::new_err("Too many arguments."));
         }
 
-        match args.get_item(0) {
-            Ok(item) => QueryParams::from_pyany(&item),
-            Err(_) => match kwargs {
-                Some(kwargs) => QueryParams::from_pydict(kwargs),
-                None => {
-                    return Ok(QueryParams {
-                        params: IndexMap::new(),
-                    })
-                }
-            },
+        let mut q = match args.get_item(0) {
+            Ok(item) => QueryParams::from_pyany(&item, options)?,
+            Err(_) => QueryParams::from_params(IndexMap::new()),
+        };
+
+        // Keyword arguments are applied over the positional params, like `dict(mapping, **kwargs)`.
+        if let Some(kwargs) = kwargs {
+            let other = QueryParams::from_pydict(kwargs, options)?;
+            q.params_mut().reserve(other.params.len());
+            q.params_mut().extend(Arc::unwrap_or_clone(other.params));
+        }
+
+        Ok(q)
+    }
+
+    /// Build from `params` like the constructor does, with parsing options. These are
+    /// kept out of the constructor, where every keyword argument is a parameter.
+    #[classmethod]
+    #[pyo3(signature = (
+        params = None,
+        /,
+        *,
+        drop_none = false,
+        bool_format = BoolFormat::Lower,
+        max_params = None,
+        max_length = None
+    ))]
+    pub fn with_options(
+        _cls: &Bound<'_, PyType>,
+        params: Option<&Bound<'_, PyAny>>,
+        drop_none: bool,
+        bool_format: BoolFormat,
+        max_params: Option<usize>,
+        max_length: Option<usize>,
+    ) -> PyResult<Self> {
+        let options = ParseOptions {
+            drop_none,
+            bool_format,
+            max_params,
+            max_length,
+        };
+        match params {
+            Some(params) => QueryParams::from_pyany(params, options),
+            None => Ok(QueryParams::from_params(IndexMap::new())),
+        }
+    }
+
+    #[classmethod]
+    #[pyo3(signature = (string, separator = "&"))]
+    pub fn parse(_cls: &Bound<'_, PyType>, string: &str, separator: &str) -> PyResult<Self> {
+        if separator.is_empty() {
+            return Err(PyValueError::new_err("Separator must not be empty."));
+        }
+        QueryParams::parse_str(_cls.py(), string, separator, ParseOptions::default())
+    }
+
+    #[classmethod]
+    pub fn from_string(_cls: &Bound<'_, PyType>, string: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let string = string.downcast::<PyString>().map_err(|_| {
+            PyTypeError::new_err(format!(
+                "from_string() expects a str, got {}",
+                string
+                    .get_type()
+                    .name()
+                    .map(|name| name.to_string())
+                    .unwrap_or_default()
+            ))
+        })?;
+        QueryParams::from_str(_cls.py(), string.to_str()?, ParseOptions::default())
+    }
+
+    #[classmethod]
+    #[pyo3(name = "from_bytes", signature = (data, encoding = "utf-8"))]
+    pub fn from_bytes_strict(_cls: &Bound<'_, PyType>, data: &Bound<'_, PyAny>, encoding: &str) -> PyResult<Self> {
+        let py = _cls.py();
+        let data = data.downcast::<PyBytes>().map_err(|_| {
+            PyTypeError::new_err(format!(
+                "from_bytes() expects bytes, got {}",
+                data.get_type()
+                    .name()
+                    .map(|name| name.to_string())
+                    .unwrap_or_default()
+            ))
+        })?;
+        let codec: String = PyModule::import(py, "codecs")?
+            .call_method1("lookup", (encoding,))?
+            .getattr("name")?
+            .extract()?;
+        if codec == "utf-8" {
+            QueryParams::from_bytes(py, data.as_bytes(), ParseOptions::default())
+        } else {
+            // Escapes are decoded with the same codec as the rest of the input.
+            let string: String = data.call_method1("decode", (encoding,))?.extract()?;
+            QueryParams::from_str_with_separator(&string, "&", ParseOptions::default(), |part| {
+                unquote_plus(py, part, &codec, DecodeErrors::Replace)
+            })
+        }
+    }
+
+    /// Build from an iterable of `(key, value)` pairs. Strings, bytes and mappings
+    /// are rejected rather than parsed.
+    #[classmethod]
+    #[pyo3(name = "from_pairs")]
+    pub fn from_pairs_strict(_cls: &Bound<'_, PyType>, pairs: &Bound<'_, PyAny>) -> PyResult<Self> {
+        if pairs.is_instance_of::<PyString>()
+            || pairs.is_instance_of::<PyBytes>()
+            || pairs.is_instance_of::<QueryParams>()
+            || pairs.hasattr("items")?
+            || pairs.try_iter().is_err()
+        {
+            return Err(PyTypeError::new_err(format!(
+                "from_pairs() expects an iterable of (key, value) pairs, got {}",
+                pairs.get_type().name()?
+            )));
+        }
+        QueryParams::from_pyany(pairs, ParseOptions::default())
+    }
+
+    /// Parse the output of `json()`: a JSON array of `[key, value]` pairs.
+    #[classmethod]
+    pub fn from_json(_cls: &Bound<'_, PyType>, string: &str) -> PyResult<Self> {
+        let py = _cls.py();
+        let pairs = PyModule::import(py, "json")?.call_method1("loads", (string,))?;
+        if !pairs.is_instance_of::<PyList>() {
+            return Err(PyValueError::new_err(format!(
+                "QueryParams JSON must be an array of [key, value] pairs, got {}",
+                pairs.get_type().name()?
+            )));
+        }
+        QueryParams::from_pyany(&pairs, ParseOptions::default())
+    }
+
+    /// Build from the `dict[str, list[str]]` returned by `urllib.parse.parse_qs`.
+    #[classmethod]
+    pub fn from_parse_qs(_cls: &Bound<'_, PyType>, params: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let params = params
+            .downcast::<PyDict>()
+            .map_err(|_| PyTypeError::new_err("from_parse_qs() expects a dict mapping keys to lists of values"))?;
+        QueryParams::from_pydict(params, ParseOptions::default())
+    }
+
+    /// Build from the `list[tuple[str, str]]` returned by `urllib.parse.parse_qsl`.
+    #[classmethod]
+    pub fn from_parse_qsl(_cls: &Bound<'_, PyType>, params: &Bound<'_, PyAny>) -> PyResult<Self> {
+        if !params.is_instance_of::<PyList>() {
+            return Err(PyTypeError::new_err(
+                "from_parse_qsl() expects a list of (key, value) pairs",
+            ));
+        }
+        QueryParams::from_pyany(params, ParseOptions::default())
+    }
+
+    #[pyo3(signature = (separator = "&"))]
+    pub fn serialize(&self, separator: &str) -> String {
+        self.join_pairs(separator)
+    }
+
+    /// The canonical query string used by AWS Signature Version 4.
+    pub fn canonical(&self) -> String {
+        let mut pairs: Vec<(Cow<'_, str>, Cow<'_, str>)> = self
+            .params
+            .iter()
+            .flat_map(|(key, values)| {
+                let key = percent_encoded(key, "");
+                values
+                    .iter()
+                    .map(move |value| (key.clone(), percent_encoded(value, "")))
+            })
+            .collect();
+        pairs.sort_unstable();
+
+        pairs
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    #[classmethod]
+    pub fn builder(_cls: &Bound<'_, PyType>) -> QueryParamsBuilder {
+        QueryParamsBuilder::default()
+    }
+
+    pub fn to_builder(&self) -> QueryParamsBuilder {
+        QueryParamsBuilder {
+            params: (*self.params).clone(),
+        }
+    }
+
+    #[pyo3(signature = (safe = SafeChars::default(), quote_via = "percent"))]
+    pub fn encode(&self, safe: SafeChars, quote_via: &str) -> PyResult<String> {
+        let safe = safe.as_str();
+        let encode = |s: &str| match quote_via {
+            "percent" => Ok(percent_encoded(s, safe).into_owned()),
+            "plus" => Ok(s
+                .split(' ')
+                .map(|part| percent_encoded(part, safe))
+                .collect::<Vec<_>>()
+                .join("+")),
+            _ => Err(PyValueError::new_err(format!(
+                "quote_via must be 'percent' or 'plus', got '{}'",
+                quote_via
+            ))),
+        };
+
+        let mut pairs = Vec::with_capacity(self.params.len());
+        for (key, values) in self.params.iter() {
+            for value in values {
+                pairs.push(format!("{}={}", encode(key)?, encode(value)?));
+            }
         }
+        Ok(pairs.join("&"))
     }
 
     pub fn keys(&self) -> Vec<String> {
-        self.params.keys().cloned().collect()
+        self.params.keys().map(|key| key.to_string()).collect()
+    }
+
+    pub fn keys_view(slf: &Bound<'_, Self>) -> QueryParamsKeysView {
+        QueryParamsKeysView {
+            params: slf.clone().unbind(),
+        }
+    }
+
+    pub fn values_view(slf: &Bound<'_, Self>) -> QueryParamsValuesView {
+        QueryParamsValuesView {
+            params: slf.clone().unbind(),
+        }
+    }
+
+    pub fn items_view(slf: &Bound<'_, Self>) -> QueryParamsItemsView {
+        QueryParamsItemsView {
+            params: slf.clone().unbind(),
+        }
     }
 
     pub fn values(&self) -> Vec<String> {
         let mut values = Vec::with_capacity(self.params.len());
         for values_list in self.params.values() {
             if let Some(value) = values_list.first() {
-                values.push(value.clone());
+                values.push(value.to_string());
             }
         }
         values
@@ -82,274 +596,1561 @@ impl QueryParams {
 
     pub fn items(&self) -> Vec<(String, String)> {
         let mut items = Vec::with_capacity(self.params.len());
-        for (key, values) in &self.params {
+        for (key, values) in self.params.iter() {
             if !values.is_empty() {
-                items.push((key.clone(), values[0].clone()));
+                items.push((key.to_string(), values[0].to_string()));
             }
         }
         items
     }
 
-    /// # NOTE
-    /// Think about the performance of this method.
-    ///
-    /// Iterating over all items and creating clones of keys and values may be not efficent.
-    /// But if we return references like `Vec<(&String, &String)>` it can lead to lifetime issues.
-    pub fn multi_items(&self) -> Vec<(String, String)> {
-        let mut items = Vec::new();
-        for (key, values) in &self.params {
+    /// Builds the Python list straight from borrowed keys and values, so each string
+    /// is copied once, into its `PyString`, rather than first into an owned `Vec`.
+    pub fn multi_items<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let items = PyList::empty(py);
+        for (key, values) in self.params.iter() {
+            let key = PyString::new(py, key);
             for value in values {
-                items.push((key.clone(), value.clone()));
+                items.append((&key, PyString::new(py, value)))?;
             }
         }
-        items
+        Ok(items)
     }
 
-    #[pyo3(signature = (key, default=None))]
-    pub fn get(&self, py: Python<'_>, key: String, default: Option<Bound<'_, PyAny>>) -> PyResult<Option<Py<PyAny>>> {
-        match self.params.get(&key) {
-            Some(values) => match values.first() {
-                Some(value) => Ok(Some(value.into_py_any(py)?)),
-                None => {
-                    if let Some(default_value) = default {
-                        Ok(Some(default_value.into_any().unbind()))
-                    } else {
-                        Ok(None)
-                    }
-                }
-            },
-            _ => {
-                if let Some(default_value) = default {
-                    Ok(Some(default_value.into_any().unbind()))
-                } else {
-                    Ok(None)
-                }
-            }
+    pub fn iter_multi_items(slf: &Bound<'_, Self>) -> QueryParamsItemsIterator {
+        QueryParamsItemsIterator {
+            params: slf.clone().unbind(),
+            key_index: 0,
+            value_index: 0,
         }
     }
 
-    pub fn get_list(&self, key: &str) -> Vec<String> {
-        match self.params.get(key) {
-            Some(values) => values.clone(),
-            None => vec![],
+    pub fn iter_values(slf: &Bound<'_, Self>) -> QueryParamsValuesIterator {
+        QueryParamsValuesIterator::new(slf.clone().unbind())
+    }
+
+    #[pyo3(name = "pairs")]
+    pub fn pairs_iter(slf: &Bound<'_, Self>) -> QueryParamsPairsIterator {
+        QueryParamsPairsIterator {
+            params: slf.clone().unbind(),
+            index: 0,
         }
     }
 
-    pub fn set(&self, key: String, value: &Bound<'_, PyAny>) -> PyResult<Self> {
-        let mut q = QueryParams {
-            params: self.params.clone(),
-        };
+    /// A JSON array of `[key, value]` pairs, which unlike an object keeps duplicate
+    /// keys and their order.
+    pub fn json(&self, py: Python<'_>) -> PyResult<String> {
+        PyModule::import(py, "json")?
+            .call_method1("dumps", (self.multi_items(py)?,))?
+            .extract()
+    }
 
-        q.params.insert(key, vec![value.to_query_param_value()?]);
-        Ok(q)
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        for (key, values) in self.params.iter() {
+            if let Some(value) = values.first() {
+                dict.set_item(&**key, &**value)?;
+            }
+        }
+        Ok(dict)
     }
 
-    pub fn add(&self, key: &str, value: &Bound<'_, PyAny>) -> PyResult<Self> {
-        let mut q = QueryParams {
-            params: self.params.clone(),
-        };
+    pub fn multi_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        for (key, values) in self.params.iter() {
+            dict.set_item(&**key, PyList::new(py, values.iter().map(|value| &**value))?)?;
+        }
+        Ok(dict)
+    }
 
-        let value = value.to_query_param_value()?;
-        q.params.entry(key.to_owned()).or_default().push(value);
-        Ok(q)
+    pub fn to_parse_qs<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        self.multi_dict(py)
     }
 
-    pub fn remove(&self, key: &str) -> Self {
-        let mut q = QueryParams {
-            params: self.params.clone(),
-        };
+    /// The pairs in the shape of `parse_qsl`, but grouped by key. When a key's values
+    /// are interleaved with other keys this is not the order `parse_qsl` returns, so
+    /// it is not a drop-in replacement for it.
+    pub fn to_parse_qsl<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        self.multi_items(py)
+    }
 
-        q.params.shift_remove(key);
-        q
+    #[pyo3(signature = (key, default=None, *, last=false))]
+    pub fn get(
+        &self,
+        py: Python<'_>,
+        key: String,
+        default: Option<Bound<'_, PyAny>>,
+        last: bool,
+    ) -> PyResult<Option<Py<PyAny>>> {
+        let value = match self.params.get(key.as_str()) {
+            Some(values) if last => values.last(),
+            Some(values) => values.first(),
+            None => None,
+        };
+        match value {
+            Some(value) => Ok(Some(value.as_ref().into_py_any(py)?)),
+            None => Ok(default.map(Bound::unbind)),
+        }
     }
 
-    #[pyo3(signature = (params = None))]
-    pub fn merge(&self, params: Option<&Bound<'_, PyAny>>) -> PyResult<Self> {
-        if let Some(params) = params {
-            let mut new_params = self.params.clone();
-            let other = QueryParams::from_pyany(params)?;
-            new_params.extend(other.params);
-            Ok(QueryParams { params: new_params })
-        } else {
-            Ok(self.clone())
+    #[pyo3(signature = (key, default=None))]
+    pub fn get_int(&self, py: Python<'_>, key: &str, default: Option<Bound<'_, PyAny>>) -> PyResult<Option<Py<PyAny>>> {
+        match self.first_value(key) {
+            // Anything that doesn't fit an `i64` is left to Python's `int()`, which also
+            // handles big integers and `_` digit separators.
+            Some(value) => match value.parse::<i64>() {
+                Ok(value) => Ok(Some(value.into_py_any(py)?)),
+                Err(_) => match py.get_type::<PyInt>().call1((value,)) {
+                    Ok(value) => Ok(Some(value.unbind())),
+                    Err(_) => Err(invalid_value(key, value, "an integer")),
+                },
+            },
+            None => Ok(default.map(Bound::unbind)),
         }
     }
 
-    pub fn __getitem__(&self, key: &str) -> PyResult<String> {
-        match self.params.get(key) {
-            Some(values) if !values.is_empty() => Ok(values[0].clone()),
-            _ => Err(PyKeyError::new_err(format!("Key '{}' not found.", key))),
+    #[pyo3(signature = (key, default=None))]
+    pub fn get_float(
+        &self,
+        py: Python<'_>,
+        key: &str,
+        default: Option<Bound<'_, PyAny>>,
+    ) -> PyResult<Option<Py<PyAny>>> {
+        match self.first_value(key) {
+            // Python's `float()` also accepts surrounding whitespace and `_` digit separators.
+            Some(value) => match value.parse::<f64>() {
+                Ok(value) => Ok(Some(value.into_py_any(py)?)),
+                Err(_) => match py.get_type::<PyFloat>().call1((value,)) {
+                    Ok(value) => Ok(Some(value.unbind())),
+                    Err(_) => Err(invalid_value(key, value, "a float")),
+                },
+            },
+            None => Ok(default.map(Bound::unbind)),
         }
     }
 
-    pub fn __contains__(&self, key: &str) -> bool {
-        self.params.contains_key(key)
+    #[pyo3(signature = (key, default=None))]
+    pub fn get_bool(
+        &self,
+        py: Python<'_>,
+        key: &str,
+        default: Option<Bound<'_, PyAny>>,
+    ) -> PyResult<Option<Py<PyAny>>> {
+        match self.first_value(key) {
+            Some(value) => match value.to_ascii_lowercase().as_str() {
+                "true" | "1" | "yes" => Ok(Some(true.into_py_any(py)?)),
+                "false" | "0" | "no" => Ok(Some(false.into_py_any(py)?)),
+                _ => Err(invalid_value(key, value, "a boolean")),
+            },
+            None => Ok(default.map(Bound::unbind)),
+        }
     }
 
-    pub fn __iter__(&self) -> QueryParamsKeysIterator {
-        QueryParamsKeysIterator {
-            params: self.keys().into_iter(),
+    pub fn item_at(&self, index: isize) -> PyResult<(String, String)> {
+        let len = self.params.len() as isize;
+        let position = if index < 0 { index + len } else { index };
+        if position < 0 {
+            return Err(PyIndexError::new_err("QueryParams index out of range"));
+        }
+
+        match self.params.get_index(position as usize) {
+            Some((key, values)) => Ok((
+                key.to_string(),
+                values
+                    .first()
+                    .map(|value| value.to_string())
+                    .unwrap_or_default(),
+            )),
+            None => Err(PyIndexError::new_err("QueryParams index out of range")),
         }
     }
 
-    pub fn __len__(&self) -> usize {
-        self.params.len()
+    #[pyo3(signature = (prefix, strip = false))]
+    pub fn get_prefixed(&self, prefix: &str, strip: bool) -> Self {
+        let params = self
+            .params
+            .iter()
+            .filter(|(key, _)| key.starts_with(prefix))
+            .map(|(key, values)| {
+                let key = if strip {
+                    Arc::from(&key[prefix.len()..])
+                } else {
+                    key.clone()
+                };
+                (key, values.clone())
+            })
+            .collect();
+        QueryParams::from_params(params)
     }
 
-    pub fn __bool__(&self) -> bool {
-        !self.params.is_empty()
+    pub fn exists(&self, key: &str, value: &Bound<'_, PyAny>) -> PyResult<bool> {
+        let value = value.to_query_param_value(key, ParseOptions::default())?;
+        Ok(self
+            .params
+            .get(key)
+            .is_some_and(|values| values.iter().any(|v| **v == *value)))
     }
 
-    pub fn __repr__(&self) -> String {
-        format!("QueryParams('{}')", self)
+    pub fn get_list(&self, key: &str) -> Vec<String> {
+        match self.params.get(key) {
+            Some(values) => values.iter().map(|value| value.to_string()).collect(),
+            None => vec![],
+        }
     }
 
-    #[allow(unused_variables)]
-    #[pyo3(signature = (params = None))]
-    pub fn update(&self, params: Option<&Bound<'_, PyAny>>) -> PyResult<Self> {
-        Err(PyRuntimeError::new_err(
-            "QueryParams are immutable since 0.18.0.  Use `q = q.merge(...)` to create an updated copy.",
-        ))
+    #[pyo3(signature = (key, value, *, bool_format = BoolFormat::Lower))]
+    pub fn set(&self, key: String, value: &Bound<'_, PyAny>, bool_format: BoolFormat) -> PyResult<Self> {
+        let mut q = self.clone();
+
+        let options = ParseOptions {
+            bool_format,
+            ..Default::default()
+        };
+        let value = value.to_query_param_value(&key, options)?;
+        q.params_mut()
+            .insert(intern_key(&key), smallvec![value.into()]);
+        Ok(q)
     }
 
-    #[allow(unused_variables)]
-    pub fn __setitem__(&self, key: String, value: String) -> PyResult<()> {
-        Err(PyRuntimeError::new_err(
-            "QueryParams are immutable since 0.18.0. Use `q = q.set(key, value)` to create an updated copy.",
-        ))
+    #[pyo3(signature = (key, value, *, bool_format = BoolFormat::Lower))]
+    pub fn add(&self, key: &str, value: &Bound<'_, PyAny>, bool_format: BoolFormat) -> PyResult<Self> {
+        let mut q = self.clone();
+
+        let options = ParseOptions {
+            bool_format,
+            ..Default::default()
+        };
+        let value = value.to_query_param_value(key, options)?;
+        q.params_mut()
+            .entry(intern_key(key))
+            .or_default()
+            .push(value.into());
+        Ok(q)
     }
-}
 
-impl QueryParams {
-    fn from_str(s: &str) -> Self {
-        let mut params: IndexMap<String, Vec<String>> = IndexMap::new();
-        if s.is_empty() {
-            return QueryParams {
-                params: IndexMap::new(),
-            };
+    #[pyo3(signature = (key, value, *, bool_format = BoolFormat::Lower))]
+    pub fn setdefault(&self, key: String, value: &Bound<'_, PyAny>, bool_format: BoolFormat) -> PyResult<Self> {
+        if self.params.contains_key(key.as_str()) {
+            return Ok(self.clone());
         }
-        for pair in s.split('&') {
-            let pair: Vec<&str> = pair.split("=").collect();
-            match pair.len() {
-                2 => {
-                    params
-                        .entry(pair[0].to_owned())
-                        .or_default()
-                        .push(pair[1].to_owned());
-                }
-                1 => {
-                    params
-                        .entry(pair[0].to_owned())
-                        .or_default()
-                        .push("".to_owned());
+        self.set(key, value, bool_format)
+    }
+
+    #[pyo3(signature = (**kwargs))]
+    pub fn copy_with(&self, kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let mut q = self.clone();
+        if let Some(kwargs) = kwargs {
+            for (key, value) in kwargs.iter() {
+                let key = key.to_query_param_key(ParseOptions::default())?;
+                if value.is_none() {
+                    q.params_mut().shift_remove(key.as_str());
+                } else {
+                    let value = value.to_query_param_value(&key, ParseOptions::default())?;
+                    q.params_mut()
+                        .insert(intern_key(&key), smallvec![value.into()]);
                 }
-                _ => {}
             }
         }
-        QueryParams { params }
+        Ok(q)
     }
 
-    fn from_pydict(dict: &Bound<'_, PyDict>) -> PyResult<Self> {
-        let mut params: IndexMap<String, Vec<String>> = IndexMap::with_capacity(dict.len());
-        for (key, value) in dict.iter() {
-            let value = if let Ok(value) = value.downcast::<PyList>() {
-                let mut values = Vec::with_capacity(value.len());
-                for item in value {
-                    values.push(item.to_query_param_value()?);
-                }
-                values
-            } else if let Ok(value) = value.downcast::<PyTuple>() {
-                let mut values = Vec::with_capacity(value.len());
-                for item in value {
-                    values.push(item.to_query_param_value()?);
-                }
-                values
-            } else {
-                vec![value.to_query_param_value()?]
-            };
+    pub fn set_list(&self, key: String, values: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let values = list_values(&key, values)?;
+        let mut q = self.clone();
 
-            params.insert(key.extract::<String>()?, value);
+        if values.is_empty() {
+            q.params_mut().shift_remove(key.as_str());
+        } else {
+            q.params_mut().insert(intern_key(&key), values);
         }
-        Ok(QueryParams { params })
+        Ok(q)
     }
 
-    fn from_pyany(obj: &Bound<'_, PyAny>) -> PyResult<Self> {
-        if obj.is_none() {
-            Ok(QueryParams {
-                params: IndexMap::new(),
-            })
-        } else if let Ok(obj) = obj.extract::<QueryParams>() {
-            Ok(QueryParams {
-                params: obj.params.clone(),
-            })
-        } else if let Ok(obj) = obj.extract::<&str>() {
-            Ok(QueryParams::from_str(&obj))
-        } else if let Ok(obj) = obj.extract::<&[u8]>() {
-            Ok(QueryParams::from_str(std::str::from_utf8(obj)?))
-        } else if let Ok(obj) = obj.downcast::<PyList>() {
-            let mut params: IndexMap<String, Vec<String>> = IndexMap::with_capacity(obj.len());
-            for item in obj.iter() {
-                let (key, value) = item.extract::<(String, String)>()?;
-                params.entry(key).or_default().push(value);
-            }
-            Ok(QueryParams { params })
-        } else if let Ok(obj) = obj.downcast::<PyTuple>() {
-            let mut params: IndexMap<String, Vec<String>> = IndexMap::with_capacity(obj.len());
-            for item in obj.iter() {
-                let (key, value) = item.extract::<(String, String)>()?;
-                params.entry(key).or_default().push(value);
-            }
-            Ok(QueryParams { params })
-        } else {
-            QueryParams::from_pydict(obj.downcast::<PyDict>()?)
-        }
+    pub fn extend(&self, pairs: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let other = QueryParams::from_pyany(pairs, ParseOptions::default())?;
+        Ok(self.appended(other))
     }
-}
 
-#[pyclass]
-#[derive(Debug, Clone)]
-pub struct QueryParamsKeysIterator {
-    params: IntoIter<String>,
-}
+    pub fn remove(&self, key: &str) -> Self {
+        let mut q = self.clone();
 
-#[pymethods]
-impl QueryParamsKeysIterator {
-    pub fn __iter__(slf: PyRefMut<Self>) -> PyRefMut<Self> {
-        slf
+        q.params_mut().shift_remove(key);
+        q
     }
 
-    pub fn __next__(&mut self) -> Option<String> {
-        self.params.next()
-    }
-}
+    pub fn without(&self, keys: &Bound<'_, PyAny>) -> PyResult<Self> {
+        // A bare string is a single key rather than an iterable of characters.
+        let keys = if keys.is_instance_of::<PyString>() {
+            HashSet::from([keys.to_query_param_key(ParseOptions::default())?])
+        } else {
+            keys.try_iter()?
+                .map(|key| key?.to_query_param_key(ParseOptions::default()))
+                .collect::<PyResult<HashSet<_>>>()?
+        };
 
-impl PartialEq for QueryParams {
-    fn eq(&self, other: &Self) -> bool {
-        let mut this = self.multi_items();
-        let mut other = other.multi_items();
-        this.sort();
-        other.sort();
-        this == other
+        let params = self
+            .params
+            .iter()
+            .filter(|(key, _)| !keys.contains(&key[..]))
+            .map(|(key, values)| (key.clone(), values.clone()))
+            .collect();
+        Ok(QueryParams::from_params(params))
     }
-}
-
-impl Eq for QueryParams {}
 
-impl std::fmt::Display for QueryParams {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut result = Vec::with_capacity(self.params.len());
-        for (key, value) in &self.params {
-            for value in value {
-                result.push(format!("{}={}", urlencode(key), urlencode(value)));
+    pub fn filter(&self, predicate: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let mut params = IndexMap::with_capacity(self.params.len());
+        for (key, values) in self.params.iter() {
+            let mut kept = SmallVec::new();
+            for value in values {
+                if predicate.call1((&**key, &**value))?.is_truthy()? {
+                    kept.push(value.clone());
+                }
+            }
+            if !kept.is_empty() {
+                params.insert(key.clone(), kept);
             }
         }
-        result.join("&");
-        write!(f, "{}", result.join("&"))
+        Ok(QueryParams::from_params(params))
     }
-}
 
-impl Hash for QueryParams {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.to_string().hash(state);
+    pub fn rename_key(&self, old: &str, new: String) -> PyResult<Self> {
+        if old == new || !self.params.contains_key(old) {
+            return Ok(self.clone());
+        }
+        if self.params.contains_key(new.as_str()) {
+            return Err(PyValueError::new_err(format!(
+                "Cannot rename '{}' to '{}': key '{}' already exists.",
+                old, new, new
+            )));
+        }
+
+        let new = intern_key(&new);
+        let params = self
+            .params
+            .iter()
+            .map(|(key, values)| {
+                let key = if &**key == old { new.clone() } else { key.clone() };
+                (key, values.clone())
+            })
+            .collect();
+        Ok(QueryParams::from_params(params))
+    }
+
+    #[pyo3(signature = (key, default = OptionalArg::default()))]
+    pub fn pop(&self, py: Python<'_>, key: &str, default: OptionalArg) -> PyResult<(Py<PyAny>, Self)> {
+        let mut q = self.clone();
+
+        let value = q
+            .params_mut()
+            .shift_remove(key)
+            .and_then(|values| values.into_iter().next());
+        match (value, default.0) {
+            (Some(value), _) => Ok((value.as_ref().into_py_any(py)?, q)),
+            (None, Some(default)) => Ok((default, q)),
+            (None, None) => Err(PyKeyError::new_err(format!("Key '{}' not found.", key))),
+        }
+    }
+
+    pub fn remove_value(&self, key: &str, value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let value = value.to_query_param_value(key, ParseOptions::default())?;
+        let mut q = self.clone();
+
+        if let Some(values) = q.params_mut().get_mut(key) {
+            if let Some(index) = values.iter().position(|v| **v == *value) {
+                values.remove(index);
+                if values.is_empty() {
+                    q.params_mut().shift_remove(key);
+                }
+            }
+        }
+        Ok(q)
+    }
+
+    pub fn sort(&self) -> Self {
+        let mut q = self.clone();
+
+        // Order by the encoded form, which is what ends up in canonical query strings.
+        q.params_mut()
+            .sort_by_cached_key(|key, _| quote_plus(key, ""));
+        q
+    }
+
+    #[pyo3(signature = (params = None))]
+    pub fn merge(&self, params: Option<&Bound<'_, PyAny>>) -> PyResult<Self> {
+        if let Some(params) = params {
+            let other = QueryParams::from_pyany(params, ParseOptions::default())?;
+            Ok(self.merged(other))
+        } else {
+            Ok(self.clone())
+        }
+    }
+
+    #[pyo3(signature = (params = None))]
+    pub fn merge_append(&self, params: Option<&Bound<'_, PyAny>>) -> PyResult<Self> {
+        if let Some(params) = params {
+            let other = QueryParams::from_pyany(params, ParseOptions::default())?;
+            Ok(self.appended(other))
+        } else {
+            Ok(self.clone())
+        }
+    }
+
+    pub fn __or__(&self, py: Python<'_>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        if !is_mergeable(other)? {
+            return Ok(py.NotImplemented());
+        }
+        let other = QueryParams::from_pyany(other, ParseOptions::default())?;
+        self.merged(other).into_py_any(py)
+    }
+
+    pub fn __ror__(&self, py: Python<'_>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        if !is_mergeable(other)? {
+            return Ok(py.NotImplemented());
+        }
+        let other = QueryParams::from_pyany(other, ParseOptions::default())?;
+        other.merged(self.clone()).into_py_any(py)
+    }
+
+    pub fn __getitem__(&self, key: &str) -> PyResult<String> {
+        match self.params.get(key) {
+            Some(values) if !values.is_empty() => Ok(values[0].to_string()),
+            _ => Err(PyKeyError::new_err(format!("Key '{}' not found.", key))),
+        }
+    }
+
+    pub fn __contains__(&self, item: &Bound<'_, PyAny>) -> PyResult<bool> {
+        if let Ok(key) = item.downcast::<PyString>() {
+            return Ok(self.params.contains_key(key.to_str()?));
+        }
+
+        if let Ok(pair) = item.downcast::<PyTuple>() {
+            if pair.len() == 2 {
+                if let Ok(key) = pair.get_item(0)?.downcast::<PyString>() {
+                    let key = key.to_str()?;
+                    return self.exists(key, &pair.get_item(1)?);
+                }
+            }
+        }
+
+        Err(PyTypeError::new_err(format!(
+            "'in <QueryParams>' requires a str key or a (key, value) tuple, got {}",
+            item.get_type().name()?
+        )))
+    }
+
+    pub fn __iter__(&self) -> QueryParamsKeysIterator {
+        QueryParamsKeysIterator {
+            params: self.keys().into_iter(),
+        }
+    }
+
+    pub fn __reversed__(&self) -> QueryParamsKeysIterator {
+        QueryParamsKeysIterator {
+            params: self
+                .params
+                .keys()
+                .rev()
+                .map(|key| key.to_string())
+                .collect::<Vec<_>>()
+                .into_iter(),
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.params.values().map(|values| values.len()).sum()
+    }
+
+    pub fn count(&self, key: &str) -> usize {
+        self.params.get(key).map_or(0, |values| values.len())
+    }
+
+    pub fn __len__(&self) -> usize {
+        self.params.len()
+    }
+
+    pub fn __bool__(&self) -> bool {
+        !self.params.is_empty()
+    }
+
+    pub fn __str__(&self, py: Python<'_>) -> String {
+        if self.rendered.get().is_none() && self.params.len() >= ALLOW_THREADS_MIN_KEYS {
+            py.allow_threads(|| self.to_query_string())
+        } else {
+            self.to_query_string()
+        }
+    }
+
+    /// The query string as ASCII bytes, copied straight from the cached rendering.
+    /// Everything outside the safe set is percent-encoded, so no check is needed.
+    pub fn __bytes__<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, self.query_str().as_bytes())
+    }
+
+    pub fn __format__(&self, spec: &str) -> PyResult<String> {
+        let mut chars = spec.chars();
+        match (chars.next(), chars.next()) {
+            (None, _) => Ok(self.to_string()),
+            (Some(c), None) if c.is_ascii_punctuation() => Ok(self.join_pairs(spec)),
+            _ if spec == "pretty" => Ok(self.join_pairs("\n")),
+            _ => Err(PyValueError::new_err(format!(
+                "Invalid format specifier '{}' for object of type 'QueryParams'",
+                spec
+            ))),
+        }
+    }
+
+    pub fn __eq__(&self, py: Python<'_>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        match QueryParams::comparable(other) {
+            Some(other) => (*self == other).into_py_any(py),
+            None => Ok(py.NotImplemented()),
+        }
+    }
+
+    pub fn __ne__(&self, py: Python<'_>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        match QueryParams::comparable(other) {
+            Some(other) => (*self != other).into_py_any(py),
+            None => Ok(py.NotImplemented()),
+        }
+    }
+
+    pub fn __hash__(&self) -> u64 {
+        *self.hash.get_or_init(|| {
+            // Must agree with `PartialEq`, which ignores ordering.
+            let mut hasher = DefaultHasher::new();
+            self.sorted_pairs().hash(&mut hasher);
+            hasher.finish()
+        })
+    }
+
+    pub fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        // Quote with Python's own rules so the repr stays eval()-able whatever the content.
+        let content = PyString::new(py, &self.to_string()).repr()?;
+        Ok(format!("QueryParams({})", content.to_str()?))
+    }
+
+    /// A constructor call over the multi-items list, which reproduces the exact
+    /// params when evaluated, duplicate keys included.
+    pub fn literal(&self, py: Python<'_>) -> PyResult<String> {
+        let items = self.multi_items(py)?.repr()?;
+        Ok(format!("QueryParams({})", items.to_str()?))
+    }
+
+    /// Includes the heap held by the map, keys and values, so memory profilers see
+    /// more than the object shell. Storage shared with copies is counted in full.
+    pub fn __sizeof__(&self) -> usize {
+        // Reference counts that precede the contents of every `Arc<str>`.
+        const ARC_HEADER: usize = 2 * std::mem::size_of::<usize>();
+        // Each map slot holds the entry itself plus its cached hash and index.
+        const SLOT: usize = std::mem::size_of::<(Arc<str>, Values)>() + 2 * std::mem::size_of::<usize>();
+
+        let mut size = std::mem::size_of::<Self>() + self.params.capacity() * SLOT;
+        for (key, values) in self.params.iter() {
+            size += ARC_HEADER + key.len();
+            if values.spilled() {
+                size += values.capacity() * std::mem::size_of::<Arc<str>>();
+            }
+            size += values
+                .iter()
+                .map(|value| ARC_HEADER + value.len())
+                .sum::<usize>();
+        }
+        if let Some(rendered) = self.rendered.get() {
+            size += rendered.capacity();
+        }
+        size
+    }
+
+    /// Instances are immutable, so a copy can share the original object.
+    pub fn __copy__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    #[allow(unused_variables)]
+    pub fn __deepcopy__(slf: Py<Self>, memo: &Bound<'_, PyAny>) -> Py<Self> {
+        slf
+    }
+
+    /// Pickle as the multi-items list, which keeps duplicate keys and their order.
+    pub fn __reduce__<'py>(slf: &Bound<'py, Self>) -> PyResult<(Bound<'py, PyType>, (Bound<'py, PyList>,))> {
+        Ok((slf.as_any().get_type(), (slf.get().multi_items(slf.py())?,)))
+    }
+
+    #[allow(unused_variables)]
+    #[pyo3(signature = (params = None))]
+    pub fn update(&self, params: Option<&Bound<'_, PyAny>>) -> PyResult<Self> {
+        Err(PyRuntimeError::new_err(
+            "QueryParams are immutable since 0.18.0.  Use `q = q.merge(...)` to create an updated copy.",
+        ))
+    }
+
+    #[allow(unused_variables)]
+    pub fn __setitem__(&self, key: String, value: String) -> PyResult<()> {
+        Err(PyRuntimeError::new_err(
+            "QueryParams are immutable since 0.18.0. Use `q = q.set(key, value)` to create an updated copy.",
+        ))
+    }
+}
+
+impl QueryParams {
+    /// Build params from `(key, value)` pairs without going through Python objects.
+    /// Repeated keys are grouped under the position where they first appear.
+    pub fn from_pairs(pairs: impl IntoIterator<Item = (String, String)>) -> Self {
+        let mut params = Params::new();
+        for (key, value) in pairs {
+            params
+                .entry(intern_key(&key))
+                .or_default()
+                .push(value.into());
+        }
+        QueryParams::from_params(params)
+    }
+
+    /// Every `(key, value)` pair, repeated keys included, in query string order.
+    pub fn multi_pairs(&self) -> QueryParamsMultiPairs<'_> {
+        QueryParamsMultiPairs {
+            params: &self.params,
+            key_index: 0,
+            value_index: 0,
+        }
+    }
+
+    pub fn to_query_string(&self) -> String {
+        self.to_string()
+    }
+
+    fn query_str(&self) -> &str {
+        self.rendered.get_or_init(|| self.join_pairs("&"))
+    }
+
+    fn from_params(params: Params) -> Self {
+        QueryParams::from_shared(Arc::new(params))
+    }
+
+    fn from_shared(params: Arc<Params>) -> Self {
+        QueryParams {
+            params,
+            rendered: OnceLock::new(),
+            hash: OnceLock::new(),
+        }
+    }
+
+    /// Mutable access to the params, copying them first if they are shared. Only
+    /// meant for building up a new instance, so any cached rendering is dropped.
+    fn params_mut(&mut self) -> &mut Params {
+        self.rendered.take();
+        self.hash.take();
+        Arc::make_mut(&mut self.params)
+    }
+
+    fn from_str(py: Python<'_>, s: &str, options: ParseOptions) -> PyResult<Self> {
+        QueryParams::parse_str(py, s, "&", options)
+    }
+
+    /// Large inputs are parsed without holding the GIL, so other Python threads can
+    /// make progress in the meantime.
+    fn parse_str(py: Python<'_>, s: &str, separator: &str, options: ParseOptions) -> PyResult<Self> {
+        if s.len() < ALLOW_THREADS_MIN_LENGTH {
+            return QueryParams::from_str_with_separator(s, separator, options, |part| Ok(unquote_plus_lossy(part)));
+        }
+        options.check_length(s.len())?;
+        // Copied so that nothing borrowed from a Python object is used without the GIL.
+        let (s, separator) = (s.to_owned(), separator.to_owned());
+        py.allow_threads(move || {
+            QueryParams::from_str_with_separator(&s, &separator, options, |part| Ok(unquote_plus_lossy(part)))
+        })
+    }
+
+    /// Split `s` into pairs, decoding each key and value with `unquote`.
+    fn from_str_with_separator(
+        s: &str,
+        separator: &str,
+        options: ParseOptions,
+        unquote: impl Fn(&str) -> PyResult<String>,
+    ) -> PyResult<Self> {
+        options.check_length(s.len())?;
+
+        let s = s.strip_prefix('?').unwrap_or(s);
+        if looks_like_url(s, separator) {
+            return Err(InvalidUrl::new(&format!(
+                "Expected a query string, but got a URL: '{}'. Use `httpx.URL(url).params` instead.",
+                s
+            ))
+            .into());
+        }
+        if s.is_empty() {
+            return Ok(QueryParams::from_params(IndexMap::new()));
+        }
+
+        let mut params: Params = IndexMap::with_capacity(options.capacity(s.matches(separator).count()));
+        let mut count = 0;
+        for pair in s.split(separator).filter(|pair| !pair.is_empty()) {
+            count += 1;
+            options.check_params(count)?;
+            // A key without `=` has an empty value.
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            params
+                .entry(intern_key(&unquote(key)?))
+                .or_default()
+                .push(unquote(value)?.into());
+        }
+        Ok(QueryParams::from_params(params))
+    }
+
+    /// Parse `&`-separated bytes directly, validating UTF-8 per key and value as they
+    /// are materialized rather than over the whole buffer up front. Large inputs are
+    /// parsed without holding the GIL, like `parse_str`.
+    fn from_bytes(py: Python<'_>, bytes: &[u8], options: ParseOptions) -> PyResult<Self> {
+        options.check_length(bytes.len())?;
+
+        let parsed = if bytes.len() < ALLOW_THREADS_MIN_LENGTH {
+            QueryParams::from_bytes_without_gil(bytes, options)?
+        } else {
+            // Copied so that nothing borrowed from a Python object is used without the GIL.
+            let bytes = bytes.to_owned();
+            py.allow_threads(move || QueryParams::from_bytes_without_gil(&bytes, options))?
+        };
+        match parsed {
+            Some(q) => Ok(q),
+            // Rare enough to take the `str` path, which builds the error message, or
+            // to report invalid UTF-8 against the whole buffer, exactly as decoding it would.
+            None => QueryParams::from_str(py, decode_utf8(py, bytes)?, options),
+        }
+    }
+
+    /// The work of `from_bytes`. `None` means the input looks like a URL or isn't
+    /// valid UTF-8, and is left for the caller to report.
+    fn from_bytes_without_gil(bytes: &[u8], options: ParseOptions) -> PyResult<Option<Self>> {
+        let input = bytes.strip_prefix(b"?").unwrap_or(bytes);
+        let first_key = input
+            .split(|&b| b == b'&' || b == b'=')
+            .next()
+            .unwrap_or_default();
+        if first_key.windows(3).any(|window| window == b"://") {
+            return Ok(None);
+        }
+
+        let mut params: Params =
+            IndexMap::with_capacity(options.capacity(input.iter().filter(|&&b| b == b'&').count()));
+        let mut count = 0;
+        for pair in input.split(|&b| b == b'&').filter(|pair| !pair.is_empty()) {
+            count += 1;
+            options.check_params(count)?;
+            let (key, value) = match pair.iter().position(|&b| b == b'=') {
+                Some(index) => (&pair[..index], &pair[index + 1..]),
+                None => (pair, &b""[..]),
+            };
+            // Splitting on ASCII bytes never breaks up a valid sequence, so the whole
+            // buffer is invalid too.
+            let (Ok(key), Ok(value)) = (std::str::from_utf8(key), std::str::from_utf8(value)) else {
+                return Ok(None);
+            };
+            params
+                .entry(intern_key(&unquote_plus_lossy(key)))
+                .or_default()
+                .push(unquote_plus_lossy(value).into());
+        }
+        Ok(Some(QueryParams::from_params(params)))
+    }
+
+    /// Build a QueryParams from the literal forms `__eq__` compares against. Anything
+    /// else, including values that fail to parse, is not comparable.
+    fn comparable(other: &Bound<'_, PyAny>) -> Option<Self> {
+        if other.is_instance_of::<QueryParams>()
+            || other.is_instance_of::<PyString>()
+            || other.is_instance_of::<PyBytes>()
+            || other.is_instance_of::<PyDict>()
+            || other.is_instance_of::<PyList>()
+        {
+            QueryParams::from_pyany(other, ParseOptions::default()).ok()
+        } else {
+            None
+        }
+    }
+
+    fn sorted_pairs(&self) -> Vec<(&str, &str)> {
+        let mut pairs: Vec<_> = self.multi_pairs().collect();
+        pairs.sort_unstable();
+        pairs
+    }
+
+    fn first_value(&self, key: &str) -> Option<&str> {
+        self.params
+            .get(key)
+            .and_then(|values| values.first())
+            .map(|value| &**value)
+    }
+
+    /// Replace our values with those of `other` for keys present in both.
+    fn merged(&self, other: QueryParams) -> Self {
+        if other.params.is_empty() {
+            return self.clone();
+        }
+        let mut params = self.with_room_for(&other);
+        params.extend(Arc::unwrap_or_clone(other.params));
+        QueryParams::from_params(params)
+    }
+
+    /// Append the values of `other` after our own, with new keys added at the end.
+    fn appended(&self, other: QueryParams) -> Self {
+        if other.params.is_empty() {
+            return self.clone();
+        }
+        let mut params = self.with_room_for(&other);
+        for (key, values) in Arc::unwrap_or_clone(other.params) {
+            params.entry(key).or_default().extend(values);
+        }
+        QueryParams::from_params(params)
+    }
+
+    /// A copy of our params with capacity for every key of `other`, so combining the
+    /// two never has to grow the map part way through.
+    fn with_room_for(&self, other: &QueryParams) -> Params {
+        let mut params = IndexMap::with_capacity(self.params.len() + other.params.len());
+        params.extend(
+            self.params
+                .iter()
+                .map(|(key, values)| (key.clone(), values.clone())),
+        );
+        params
+    }
+
+    fn join_pairs(&self, separator: &str) -> String {
+        // Most parameters need no escaping, so the unencoded length is a good estimate.
+        let capacity = self
+            .params
+            .iter()
+            .map(|(key, values)| {
+                values
+                    .iter()
+                    .map(|value| key.len() + value.len() + 2)
+                    .sum::<usize>()
+            })
+            .sum();
+        let mut result = String::with_capacity(capacity);
+        let mut first = true;
+        for (key, values) in self.params.iter() {
+            for value in values {
+                if !first {
+                    result.push_str(separator);
+                }
+                first = false;
+                quote_plus_into(&mut result, key, "");
+                result.push('=');
+                quote_plus_into(&mut result, value, "");
+            }
+        }
+        result
+    }
+
+    fn from_pydict(dict: &Bound<'_, PyDict>, options: ParseOptions) -> PyResult<Self> {
+        let mut params: Params = IndexMap::with_capacity(dict.len());
+        let mut count = 0;
+        for (key, value) in dict.iter() {
+            if options.drop_none && value.is_none() {
+                continue;
+            }
+            let key = key.to_query_param_key(options)?;
+            let values = value.to_query_param_values(&key, options)?;
+            if values.is_empty() {
+                // Keys without values are never stored, so `len`, `keys` and `items` always agree.
+                continue;
+            }
+            count += values.len();
+            options.check_params(count)?;
+            params.insert(intern_key(&key), values);
+        }
+        Ok(QueryParams::from_params(params))
+    }
+
+    /// Build from any object exposing a mapping-style `items()`, such as `Mapping`
+    /// subclasses or `MultiDict`. Repeated keys accumulate their values.
+    fn from_mapping(mapping: &Bound<'_, PyAny>, options: ParseOptions) -> PyResult<Self> {
+        let mut params: Params = IndexMap::new();
+        let mut count = 0;
+        for item in mapping.call_method0("items")?.try_iter()? {
+            let (key, value) = item?.extract::<(Bound<'_, PyAny>, Bound<'_, PyAny>)>()?;
+            if options.drop_none && value.is_none() {
+                continue;
+            }
+            let key = key.to_query_param_key(options)?;
+            let values = value.to_query_param_values(&key, options)?;
+            if values.is_empty() {
+                continue;
+            }
+            count += values.len();
+            options.check_params(count)?;
+            params.entry(intern_key(&key)).or_default().extend(values);
+        }
+        Ok(QueryParams::from_params(params))
+    }
+
+    /// Build from an iterator of `(key, value)` pairs. `capacity` is a hint for the
+    /// number of keys, capped at `max_params`.
+    fn from_pairs_iter<'py>(
+        items: impl Iterator<Item = PyResult<Bound<'py, PyAny>>>,
+        capacity: usize,
+        options: ParseOptions,
+    ) -> PyResult<Self> {
+        let capacity = options
+            .max_params
+            .map_or(capacity, |max_params| capacity.min(max_params));
+        let mut params: Params = IndexMap::with_capacity(capacity);
+        let mut count = 0;
+        for (index, item) in items.enumerate() {
+            let (key, value) = extract_pair(&item?, index, options)?;
+            if !(options.drop_none && value.is_none()) {
+                let value = value.to_query_param_value(&key, options)?;
+                params
+                    .entry(intern_key(&key))
+                    .or_default()
+                    .push(value.into());
+                count += 1;
+                options.check_params(count)?;
+            }
+        }
+        Ok(QueryParams::from_params(params))
+    }
+
+    fn from_pyany(obj: &Bound<'_, PyAny>, options: ParseOptions) -> PyResult<Self> {
+        if obj.is_none() {
+            Ok(QueryParams::from_params(IndexMap::new()))
+        } else if let Ok(obj) = obj.downcast::<QueryParams>() {
+            Ok(obj.get().clone())
+        } else if let Ok(s) = obj.extract::<&str>() {
+            QueryParams::from_str(obj.py(), s, options)
+        } else if let Ok(bytes) = obj.extract::<&[u8]>() {
+            QueryParams::from_bytes(obj.py(), bytes, options)
+        } else if let Ok(obj) = obj.downcast::<PyList>() {
+            QueryParams::from_pairs_iter(obj.iter().map(Ok), obj.len(), options)
+        } else if let Ok(obj) = obj.downcast::<PyTuple>() {
+            QueryParams::from_pairs_iter(obj.iter().map(Ok), obj.len(), options)
+        } else if let Ok(obj) = obj.downcast::<PyDict>() {
+            QueryParams::from_pydict(obj, options)
+        } else if obj.hasattr("items")? {
+            QueryParams::from_mapping(obj, options)
+        } else if let Ok(iter) = obj.try_iter() {
+            QueryParams::from_pairs_iter(iter, 0, options)
+        } else {
+            Err(PyTypeError::new_err(format!(
+                "QueryParams cannot be constructed from {}",
+                obj.get_type().name()?
+            )))
+        }
+    }
+}
+
+/// Values for `set_list()`, which only accepts an explicit list or tuple.
+fn list_values(key: &str, values: &Bound<'_, PyAny>) -> PyResult<Values> {
+    if !(values.is_instance_of::<PyList>() || values.is_instance_of::<PyTuple>()) {
+        return Err(PyTypeError::new_err(format!(
+            "set_list() expects a list or tuple of values, got {}",
+            values.get_type().name()?
+        )));
+    }
+    values.to_query_param_values(key, ParseOptions::default())
+}
+
+/// A stand-in for `urllib.parse.urlencode` that coerces values the same way as
+/// `QueryParams`. Pairs are rendered in the order given, without grouping repeated keys.
+#[pyfunction]
+#[pyo3(signature = (query, doseq = true, safe = SafeChars::default(), quote_via = "quote_plus"))]
+pub fn urlencode(query: &Bound<'_, PyAny>, doseq: bool, safe: SafeChars, quote_via: &str) -> PyResult<String> {
+    let quote_via: fn(&mut String, &str, &str) = match quote_via {
+        "quote_plus" => quote_plus_into,
+        "quote" => |out: &mut String, s: &str, safe: &str| out.push_str(&percent_encoded(s, safe)),
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "quote_via must be 'quote_plus' or 'quote', got '{}'",
+                quote_via
+            )))
+        }
+    };
+
+    let items = if query.is_instance_of::<PyString>() || query.is_instance_of::<PyBytes>() {
+        return Err(PyTypeError::new_err(
+            "urlencode() expects a mapping or a sequence of (key, value) pairs",
+        ));
+    } else if query.hasattr("items")? {
+        query.call_method0("items")?.try_iter()?
+    } else {
+        query.try_iter()?
+    };
+
+    let options = ParseOptions::default();
+    let mut result = String::new();
+    for (index, item) in items.enumerate() {
+        let (key, value) = extract_pair(&item?, index, options)?;
+        let values = if doseq {
+            value.to_query_param_values(&key, options)?
+        } else if value.is_instance_of::<PyList>()
+            || value.is_instance_of::<PyTuple>()
+            || value.is_instance_of::<PySet>()
+            || value.is_instance_of::<PyFrozenSet>()
+        {
+            // Without `doseq`, urllib quotes the `str()` of a sequence as a single value.
+            smallvec![value.str()?.to_str()?.into()]
+        } else {
+            smallvec![value.to_query_param_value(&key, options)?.into()]
+        };
+        for value in values {
+            if !result.is_empty() {
+                result.push('&');
+            }
+            quote_via(&mut result, &key, safe.as_str());
+            result.push('=');
+            quote_via(&mut result, &value, safe.as_str());
+        }
+    }
+    Ok(result)
+}
+
+fn invalid_value(key: &str, value: &str, expected: &str) -> PyErr {
+    PyValueError::new_err(format!(
+        "Query parameter '{}' has value '{}', which is not {}.",
+        key, value, expected
+    ))
+}
+
+/// Operands accepted by `|`, mirroring `dict`: mappings and pair sequences, but not
+/// query strings.
+fn is_mergeable(other: &Bound<'_, PyAny>) -> PyResult<bool> {
+    Ok(other.is_instance_of::<QueryParams>()
+        || other.is_instance_of::<PyDict>()
+        || other.is_instance_of::<PyList>()
+        || other.is_instance_of::<PyTuple>()
+        || other.hasattr("items")?)
+}
+
+fn extract_pair<'py>(
+    item: &Bound<'py, PyAny>,
+    index: usize,
+    options: ParseOptions,
+) -> PyResult<(String, Bound<'py, PyAny>)> {
+    let pair = match item.downcast::<PySequence>() {
+        Ok(pair) if !item.is_instance_of::<PyString>() && !item.is_instance_of::<PyBytes>() => pair,
+        _ => {
+            return Err(PyTypeError::new_err(format!(
+                "QueryParams element #{} must be a (key, value) pair, got {}",
+                index,
+                item.get_type().name()?
+            )))
+        }
+    };
+
+    let len = pair.len()?;
+    if len != 2 {
+        return Err(PyValueError::new_err(format!(
+            "QueryParams element #{} has length {}; 2 is required",
+            index, len
+        )));
+    }
+
+    Ok((pair.get_item(0)?.to_query_param_key(options)?, pair.get_item(1)?))
+}
+
+/// A mutable counterpart to `QueryParams` for assembling parameters in place,
+/// without cloning the map on every change.
+#[pyclass]
+#[derive(Debug, Default)]
+pub struct QueryParamsBuilder {
+    params: Params,
+}
+
+#[pymethods]
+impl QueryParamsBuilder {
+    #[new]
+    #[pyo3(signature = (params = None))]
+    pub fn new(params: Option<&Bound<'_, PyAny>>) -> PyResult<Self> {
+        match params {
+            Some(params) => Ok(QueryParamsBuilder {
+                params: Arc::unwrap_or_clone(QueryParams::from_pyany(params, ParseOptions::default())?.params),
+            }),
+            None => Ok(QueryParamsBuilder::default()),
+        }
+    }
+
+    #[pyo3(signature = (key, value, *, bool_format = BoolFormat::Lower))]
+    pub fn set<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        key: String,
+        value: &Bound<'_, PyAny>,
+        bool_format: BoolFormat,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let options = ParseOptions {
+            bool_format,
+            ..Default::default()
+        };
+        let value = value.to_query_param_value(&key, options)?;
+        slf.params.insert(intern_key(&key), smallvec![value.into()]);
+        Ok(slf)
+    }
+
+    #[pyo3(signature = (key, value, *, bool_format = BoolFormat::Lower))]
+    pub fn add<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        key: String,
+        value: &Bound<'_, PyAny>,
+        bool_format: BoolFormat,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let options = ParseOptions {
+            bool_format,
+            ..Default::default()
+        };
+        let value = value.to_query_param_value(&key, options)?;
+        slf.params
+            .entry(intern_key(&key))
+            .or_default()
+            .push(value.into());
+        Ok(slf)
+    }
+
+    pub fn set_list<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        key: String,
+        values: &Bound<'_, PyAny>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let values = list_values(&key, values)?;
+        if values.is_empty() {
+            slf.params.shift_remove(key.as_str());
+        } else {
+            slf.params.insert(intern_key(&key), values);
+        }
+        Ok(slf)
+    }
+
+    pub fn remove<'py>(mut slf: PyRefMut<'py, Self>, key: &str) -> PyRefMut<'py, Self> {
+        slf.params.shift_remove(key);
+        slf
+    }
+
+    pub fn update<'py>(mut slf: PyRefMut<'py, Self>, params: &Bound<'_, PyAny>) -> PyResult<PyRefMut<'py, Self>> {
+        let other = QueryParams::from_pyany(params, ParseOptions::default())?;
+        slf.params.reserve(other.params.len());
+        slf.params.extend(Arc::unwrap_or_clone(other.params));
+        Ok(slf)
+    }
+
+    /// Move the accumulated parameters into a new `QueryParams`, leaving the builder empty.
+    pub fn build(&mut self) -> QueryParams {
+        QueryParams::from_params(std::mem::take(&mut self.params))
+    }
+
+    pub fn __setitem__(&mut self, key: String, value: &Bound<'_, PyAny>) -> PyResult<()> {
+        let value = value.to_query_param_value(&key, ParseOptions::default())?;
+        self.params
+            .insert(intern_key(&key), smallvec![value.into()]);
+        Ok(())
+    }
+
+    pub fn __delitem__(&mut self, key: &str) -> PyResult<()> {
+        match self.params.shift_remove(key) {
+            Some(_) => Ok(()),
+            None => Err(PyKeyError::new_err(format!("Key '{}' not found.", key))),
+        }
+    }
+
+    pub fn __contains__(&self, key: &str) -> bool {
+        self.params.contains_key(key)
+    }
+
+    pub fn __len__(&self) -> usize {
+        self.params.len()
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct QueryParamsKeysIterator {
+    params: IntoIter<String>,
+}
+
+#[pymethods]
+impl QueryParamsKeysIterator {
+    pub fn __iter__(slf: PyRefMut<Self>) -> PyRefMut<Self> {
+        slf
+    }
+
+    pub fn __next__(&mut self) -> Option<String> {
+        self.params.next()
+    }
+}
+
+/// Borrowing iterator over every `(key, value)` pair of a `QueryParams`, returned by
+/// `QueryParams::multi_pairs`.
+#[derive(Debug)]
+pub struct QueryParamsMultiPairs<'a> {
+    params: &'a Params,
+    key_index: usize,
+    value_index: usize,
+}
+
+impl<'a> Iterator for QueryParamsMultiPairs<'a> {
+    type Item = (&'a str, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (key, values) = self.params.get_index(self.key_index)?;
+            if let Some(value) = values.get(self.value_index) {
+                self.value_index += 1;
+                return Some((key, value));
+            }
+            self.key_index += 1;
+            self.value_index = 0;
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Debug)]
+pub struct QueryParamsItemsIterator {
+    params: Py<QueryParams>,
+    key_index: usize,
+    value_index: usize,
+}
+
+#[pymethods]
+impl QueryParamsItemsIterator {
+    pub fn __iter__(slf: PyRefMut<Self>) -> PyRefMut<Self> {
+        slf
+    }
+
+    pub fn __next__(&mut self) -> Option<(String, String)> {
+        // QueryParams is frozen, so walking it by position is safe without copying.
+        let params = &self.params.get().params;
+        loop {
+            let (key, values) = params.get_index(self.key_index)?;
+            if let Some(value) = values.get(self.value_index) {
+                self.value_index += 1;
+                return Some((key.to_string(), value.to_string()));
+            }
+            self.key_index += 1;
+            self.value_index = 0;
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Debug)]
+pub struct QueryParamsPairsIterator {
+    params: Py<QueryParams>,
+    index: usize,
+}
+
+#[pymethods]
+impl QueryParamsPairsIterator {
+    pub fn __iter__(slf: PyRefMut<Self>) -> PyRefMut<Self> {
+        slf
+    }
+
+    pub fn __next__(&mut self) -> Option<(String, String)> {
+        let params = &self.params.get().params;
+        loop {
+            let (key, values) = params.get_index(self.index)?;
+            self.index += 1;
+            if let Some(value) = values.first() {
+                return Some((key.to_string(), value.to_string()));
+            }
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Debug)]
+pub struct QueryParamsValuesIterator {
+    params: Py<QueryParams>,
+    index: usize,
+}
+
+impl QueryParamsValuesIterator {
+    /// QueryParams is immutable, so the iterator is unaffected when the caller
+    /// rebinds its variable to an updated copy.
+    pub fn new(params: Py<QueryParams>) -> Self {
+        QueryParamsValuesIterator { params, index: 0 }
+    }
+}
+
+#[pymethods]
+impl QueryParamsValuesIterator {
+    pub fn __iter__(slf: PyRefMut<Self>) -> PyRefMut<Self> {
+        slf
+    }
+
+    pub fn __next__(&mut self) -> Option<String> {
+        let params = &self.params.get().params;
+        loop {
+            let (_, values) = params.get_index(self.index)?;
+            self.index += 1;
+            if let Some(value) = values.first() {
+                return Some(value.to_string());
+            }
+        }
+    }
+}
+
+/// A live view over the keys, analogous to `dict.keys()`.
+#[pyclass(frozen)]
+#[derive(Debug)]
+pub struct QueryParamsKeysView {
+    params: Py<QueryParams>,
+}
+
+#[pymethods]
+impl QueryParamsKeysView {
+    pub fn __len__(&self) -> usize {
+        self.params.get().params.len()
+    }
+
+    pub fn __contains__(&self, key: &Bound<'_, PyAny>) -> PyResult<bool> {
+        match key.downcast::<PyString>() {
+            Ok(key) => Ok(self.params.get().params.contains_key(key.to_str()?)),
+            Err(_) => Ok(false),
+        }
+    }
+
+    pub fn __iter__(&self) -> QueryParamsKeysIterator {
+        self.params.get().__iter__()
+    }
+}
+
+/// A live view over the first value of each key, analogous to `dict.values()`.
+#[pyclass(frozen)]
+#[derive(Debug)]
+pub struct QueryParamsValuesView {
+    params: Py<QueryParams>,
+}
+
+#[pymethods]
+impl QueryParamsValuesView {
+    pub fn __len__(&self) -> usize {
+        self.params.get().params.len()
+    }
+
+    pub fn __contains__(&self, value: &Bound<'_, PyAny>) -> PyResult<bool> {
+        match value.downcast::<PyString>() {
+            Ok(value) => {
+                let value = value.to_str()?;
+                Ok(self
+                    .params
+                    .get()
+                    .params
+                    .values()
+                    .any(|values| values.first().is_some_and(|v| &**v == value)))
+            }
+            Err(_) => Ok(false),
+        }
+    }
+
+    pub fn __iter__(&self, py: Python<'_>) -> QueryParamsValuesIterator {
+        QueryParamsValuesIterator::new(self.params.clone_ref(py))
+    }
+}
+
+/// A live view over `(key, first_value)` pairs, analogous to `dict.items()`.
+#[pyclass(frozen)]
+#[derive(Debug)]
+pub struct QueryParamsItemsView {
+    params: Py<QueryParams>,
+}
+
+#[pymethods]
+impl QueryParamsItemsView {
+    pub fn __len__(&self) -> usize {
+        self.params.get().params.len()
+    }
+
+    pub fn __contains__(&self, item: &Bound<'_, PyAny>) -> PyResult<bool> {
+        match item.extract::<(String, String)>() {
+            Ok((key, value)) => Ok(self.params.get().first_value(&key) == Some(value.as_str())),
+            Err(_) => Ok(false),
+        }
+    }
+
+    pub fn __iter__(&self, py: Python<'_>) -> QueryParamsPairsIterator {
+        QueryParamsPairsIterator {
+            params: self.params.clone_ref(py),
+            index: 0,
+        }
+    }
+}
+
+/// Pairs are stored percent-decoded, so `a%20b`, `a+b` and `a b` all compare equal.
+impl PartialEq for QueryParams {
+    fn eq(&self, other: &Self) -> bool {
+        if self.params.len() != other.params.len() || self.total() != other.total() {
+            return false;
+        }
+
+        // With equal key counts, every key on our side being present on the other
+        // means the key sets match. Values are then compared per key, ignoring order.
+        self.params.iter().all(|(key, values)| {
+            let Some(other_values) = other.params.get(key) else {
+                return false;
+            };
+            match (values.as_slice(), other_values.as_slice()) {
+                ([value], [other_value]) => value == other_value,
+                _ if values.len() != other_values.len() => false,
+                _ => {
+                    let mut values: Vec<&str> = values.iter().map(|value| &**value).collect();
+                    let mut other_values: Vec<&str> = other_values.iter().map(|value| &**value).collect();
+                    values.sort_unstable();
+                    other_values.sort_unstable();
+                    values == other_values
+                }
+            }
+        })
+    }
+}
+
+impl Eq for QueryParams {}
+
+impl std::fmt::Display for QueryParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.query_str())
+    }
+}
+
+impl Hash for QueryParams {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.__hash__());
+    }
+}
+
+impl<'a> IntoIterator for &'a QueryParams {
+    type Item = (&'a str, &'a str);
+    type IntoIter = QueryParamsMultiPairs<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.multi_pairs()
+    }
+}
+
+impl IntoIterator for QueryParams {
+    type Item = (String, String);
+    type IntoIter = std::vec::IntoIter<(String, String)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.multi_pairs()
+            .map(|(key, value)| (key.to_owned(), value.to_owned()))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+impl FromIterator<(String, String)> for QueryParams {
+    fn from_iter<I: IntoIterator<Item = (String, String)>>(iter: I) -> Self {
+        QueryParams::from_pairs(iter)
+    }
+}
+
+/// Serialized as a sequence of `(key, value)` pairs so duplicate keys and their order
+/// survive the round trip.
+#[cfg(feature = "serde")]
+impl serde::Serialize for QueryParams {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.multi_pairs())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for QueryParams {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<(String, String)>::deserialize(deserializer).map(QueryParams::from_pairs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owned(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|&(key, value)| (key.to_owned(), value.to_owned()))
+            .collect()
+    }
+
+    const FIXTURES: &[&[(&str, &str)]] = &[
+        &[],
+        &[("a", "1")],
+        &[("a", "1"), ("b", "2"), ("a", "3")],
+        &[("b", "2"), ("a", "1"), ("b", "3"), ("c", ""), ("b", "4")],
+        &[("", ""), ("k y", "a+b/c é"), ("東京", "☃")],
+    ];
+
+    #[test]
+    fn collect_groups_duplicate_keys_at_first_position() {
+        let q: QueryParams = owned(&[("b", "2"), ("a", "1"), ("b", "3")])
+            .into_iter()
+            .collect();
+        assert_eq!(
+            q.multi_pairs().collect::<Vec<_>>(),
+            [("b", "2"), ("b", "3"), ("a", "1")]
+        );
+        assert_eq!(q.to_query_string(), "b=2&b=3&a=1");
+    }
+
+    #[test]
+    fn iterating_borrowed_params_round_trips() {
+        for pairs in FIXTURES {
+            let q: QueryParams = owned(pairs).into_iter().collect();
+            let collected: QueryParams = (&q)
+                .into_iter()
+                .map(|(key, value)| (key.to_owned(), value.to_owned()))
+                .collect();
+            assert_eq!(collected, q);
+            assert_eq!(collected.to_query_string(), q.to_query_string());
+        }
+    }
+
+    #[test]
+    fn iterating_owned_params_round_trips() {
+        for pairs in FIXTURES {
+            let q: QueryParams = owned(pairs).into_iter().collect();
+            let expected: Vec<(String, String)> = q
+                .multi_pairs()
+                .map(|(k, v)| (k.to_owned(), v.to_owned()))
+                .collect();
+            assert_eq!(q.clone().into_iter().collect::<Vec<_>>(), expected);
+            assert_eq!(q.clone().into_iter().collect::<QueryParams>(), q);
+        }
+    }
+
+    #[test]
+    fn copies_share_storage_until_changed() {
+        let q: QueryParams = owned(&[("a", "1"), ("b", "2"), ("a", "3")])
+            .into_iter()
+            .collect();
+        let mut copy = q.clone();
+        assert!(Arc::ptr_eq(&q.params, &copy.params));
+
+        copy.params_mut().shift_remove("b");
+        assert!(!Arc::ptr_eq(&q.params, &copy.params));
+        assert_eq!(copy.to_query_string(), "a=1&a=3");
+        assert_eq!(q.to_query_string(), "a=1&a=3&b=2");
+    }
+
+    #[test]
+    fn common_keys_share_one_allocation() {
+        let pages: Vec<QueryParams> = (0..10_000)
+            .map(|i| {
+                let page = i.to_string();
+                QueryParams::from_pairs(owned(&[("page", &page), ("sort", "id"), ("pagx", &page)]))
+            })
+            .collect();
+        let key = |q: &QueryParams, index: usize| q.params.get_index(index).unwrap().0.clone();
+        for page in &pages[1..] {
+            assert!(Arc::ptr_eq(&key(&pages[0], 0), &key(page, 0)));
+            assert!(Arc::ptr_eq(&key(&pages[0], 1), &key(page, 1)));
+            assert!(!Arc::ptr_eq(&key(&pages[0], 2), &key(page, 2)));
+        }
+    }
+
+    #[test]
+    fn filter_and_collect() {
+        let q: QueryParams = owned(&[("a", "1"), ("b", "2"), ("a", "3")])
+            .into_iter()
+            .collect();
+        let filtered: QueryParams = q.into_iter().filter(|(key, _)| key == "a").collect();
+        assert_eq!(filtered.to_query_string(), "a=1&a=3");
     }
 }
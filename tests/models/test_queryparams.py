@@ -133,3 +133,1545 @@ def test_queryparams_are_hashable():
     )
 
     assert len(set(params)) == 2
+
+
+def test_queryparams_percent_decoding():
+    q = httpx.QueryParams("a%20key=hello%26world")
+    assert q["a key"] == "hello&world"
+    assert q.get("a%20key") is None
+    assert list(q.keys()) == ["a key"]
+
+    q = httpx.QueryParams("a%20b=c%26d")
+    assert str(q) == "a+b=c%26d"
+    assert httpx.QueryParams(str(q)) == q
+
+
+def test_queryparams_malformed_percent_encoding():
+    q = httpx.QueryParams("a=%G1&b=%2&c=100%")
+    assert q["a"] == "%G1"
+    assert q["b"] == "%2"
+    assert q["c"] == "100%"
+
+
+def test_queryparams_plus_as_space():
+    q = httpx.QueryParams("name=John+Doe")
+    assert q["name"] == "John Doe"
+
+    q = httpx.QueryParams("a=b+c")
+    assert q["a"] == "b c"
+    assert str(q) == "a=b+c"
+
+    q = httpx.QueryParams("a+b=%2B")
+    assert q["a b"] == "+"
+
+
+def test_queryparams_split_on_first_equals():
+    q = httpx.QueryParams("token=abc=def==")
+    assert q["token"] == "abc=def=="
+
+    q = httpx.QueryParams("a=dGVzdA==&b=1")
+    assert q.multi_items() == [("a", "dGVzdA=="), ("b", "1")]
+
+    q = httpx.QueryParams("=")
+    assert q.multi_items() == [("", "")]
+
+    q = httpx.QueryParams("=value")
+    assert q[""] == "value"
+
+
+def test_queryparams_leading_question_mark():
+    q = httpx.QueryParams("?page=2&size=10")
+    assert list(q.keys()) == ["page", "size"]
+    assert str(q) == "page=2&size=10"
+
+    q = httpx.QueryParams("?")
+    assert q == httpx.QueryParams()
+    assert not q
+
+    q = httpx.QueryParams("a=?b&c?=d")
+    assert q.multi_items() == [("a", "?b"), ("c?", "d")]
+
+    q = httpx.QueryParams("??a=1")
+    assert list(q.keys()) == ["?a"]
+
+
+def test_queryparams_parse_with_separator():
+    q = httpx.QueryParams.parse("a=1;b=2", separator=";")
+    assert q == httpx.QueryParams("a=1&b=2")
+    assert q.serialize(separator=";") == "a=1;b=2"
+    assert httpx.QueryParams.parse(q.serialize(separator=";"), separator=";") == q
+
+    q = httpx.QueryParams.parse("a=1&b=2;c=3", separator=";")
+    assert q.multi_items() == [("a", "1&b=2"), ("c", "3")]
+
+    q = httpx.QueryParams.parse("a=1&b=2;c=3")
+    assert q.multi_items() == [("a", "1"), ("b", "2;c=3")]
+
+    q = httpx.QueryParams("a=1;b=2")
+    assert q.multi_items() == [("a", "1;b=2")]
+    assert q.serialize() == str(q)
+
+    with pytest.raises(ValueError):
+        httpx.QueryParams.parse("a=1", separator="")
+
+
+def test_queryparams_skip_empty_pairs():
+    q = httpx.QueryParams("a=1&&b=2&")
+    assert len(q) == 2
+    assert "" not in q
+    assert str(q) == "a=1&b=2"
+
+    q = httpx.QueryParams("&&&")
+    assert len(q) == 0
+    assert not q
+    assert str(q) == ""
+
+
+def test_queryparams_from_bytes():
+    q = httpx.QueryParams("café=naïve".encode("utf-8"))
+    assert q["café"] == "naïve"
+
+
+@pytest.mark.parametrize(
+    "source,position",
+    [
+        (b"a=\xff\xfe", 2),
+        (b"\xe9=1", 0),
+        (b"a=1&b\xc3=2", 5),
+    ],
+)
+def test_queryparams_from_invalid_utf8_bytes(source, position):
+    with pytest.raises(UnicodeDecodeError) as exc_info:
+        httpx.QueryParams(source)
+    assert exc_info.value.start == position
+
+
+def test_queryparams_from_iterables():
+    q = httpx.QueryParams(("a", "1") for _ in range(1))
+    assert q.multi_items() == [("a", "1")]
+
+    q = httpx.QueryParams({"a": "1", "b": "2"}.items())
+    assert q.multi_items() == [("a", "1"), ("b", "2")]
+
+    q = httpx.QueryParams(iter([("a", 1), ("a", True), ("b", None)]))
+    assert q.multi_items() == [("a", "1"), ("a", "true"), ("b", "")]
+
+
+def test_queryparams_from_iterable_with_invalid_element():
+    with pytest.raises(TypeError, match="#1"):
+        httpx.QueryParams(iter([("a", "1"), 123]))
+
+
+def test_queryparams_from_unsupported_type():
+    with pytest.raises(TypeError):
+        httpx.QueryParams(123)
+
+
+def test_queryparams_from_mapping():
+    import collections.abc
+
+    class Params(collections.abc.Mapping):
+        def __init__(self, data):
+            self._data = data
+
+        def __getitem__(self, key):
+            return self._data[key]
+
+        def __iter__(self):
+            return iter(self._data)
+
+        def __len__(self):
+            return len(self._data)
+
+    q = httpx.QueryParams(Params({"a": "1", "b": ["2", "3"]}))
+    assert q.multi_items() == [("a", "1"), ("b", "2"), ("b", "3")]
+
+
+def test_queryparams_from_duck_typed_items():
+    class MultiDict:
+        def items(self):
+            return [("a", "1"), ("b", "2"), ("a", "3")]
+
+    q = httpx.QueryParams(MultiDict())
+    assert q.multi_items() == [("a", "1"), ("a", "3"), ("b", "2")]
+
+
+def test_queryparams_from_list_of_lists():
+    q = httpx.QueryParams([["a", "1"], ["b", "2"]])
+    assert q.multi_items() == [("a", "1"), ("b", "2")]
+
+    q = httpx.QueryParams((["a", "1"], ("a", "2")))
+    assert q.get_list("a") == ["1", "2"]
+
+
+def test_queryparams_pair_with_wrong_length():
+    with pytest.raises(ValueError, match="#1"):
+        httpx.QueryParams([["a", "1"], ["b", "2", "3"]])
+
+    with pytest.raises(ValueError, match="#0"):
+        httpx.QueryParams([["a"]])
+
+    with pytest.raises(TypeError, match="#0"):
+        httpx.QueryParams(["ab"])
+
+
+def test_queryparams_set_values():
+    q = httpx.QueryParams({"tags": {"b", "c", "a"}})
+    assert q.get_list("tags") == ["a", "b", "c"]
+    assert str(q) == "tags=a&tags=b&tags=c"
+
+    q = httpx.QueryParams({"ids": frozenset([3, 1, 2])})
+    assert str(q) == "ids=1&ids=2&ids=3"
+
+    q = httpx.QueryParams({"empty": set()})
+    assert q.get_list("empty") == []
+    assert "empty" not in q
+
+
+@pytest.mark.parametrize(
+    "value",
+    [0.1, 1.0, -0.0, 1e20, 1e-7, float("inf"), float("-inf"), 10**100, -(10**100)],
+)
+def test_queryparams_numeric_values(value):
+    q = httpx.QueryParams({"a": value})
+    assert q["a"] == str(value)
+    assert q == httpx.QueryParams(str(q))
+
+
+def test_queryparams_decimal_and_fraction_values():
+    from decimal import Decimal
+    from fractions import Fraction
+
+    q = httpx.QueryParams({"amount": Decimal("10.50")})
+    assert q["amount"] == "10.50"
+
+    q = httpx.QueryParams({"amount": Decimal("1E+3")})
+    assert q["amount"] == "1E+3"
+
+    q = httpx.QueryParams({"ratio": Fraction(1, 3)})
+    assert q["ratio"] == "1/3"
+    assert str(q) == "ratio=1%2F3"
+
+
+@pytest.mark.parametrize("value", [object(), 1 + 2j])
+def test_queryparams_unsupported_value_type(value):
+    with pytest.raises(TypeError):
+        httpx.QueryParams({"a": value})
+
+    with pytest.raises(TypeError):
+        httpx.QueryParams().set("a", value)
+
+
+def test_queryparams_datetime_values():
+    import datetime
+
+    q = httpx.QueryParams(created_after=datetime.datetime(2024, 1, 1))
+    assert q["created_after"] == "2024-01-01T00:00:00"
+    assert str(q) == "created_after=2024-01-01T00%3A00%3A00"
+
+    tz = datetime.timezone(datetime.timedelta(hours=2))
+    q = httpx.QueryParams({"at": datetime.datetime(2024, 1, 1, 12, 30, tzinfo=tz)})
+    assert q["at"] == "2024-01-01T12:30:00+02:00"
+
+    q = httpx.QueryParams({"on": datetime.date(2024, 2, 29)})
+    assert q["on"] == "2024-02-29"
+
+    q = httpx.QueryParams({"at": datetime.time(9, 5, 1)})
+    assert q["at"] == "09:05:01"
+
+
+def test_queryparams_enum_values():
+    import enum
+
+    class Color(enum.Enum):
+        RED = "red"
+        GREEN = 2
+
+    class Status(str, enum.Enum):
+        ACTIVE = "active"
+
+        def __str__(self):
+            return "Status.ACTIVE"
+
+    class Level(enum.IntEnum):
+        HIGH = 3
+
+    q = httpx.QueryParams({"color": Color.RED})
+    assert q["color"] == "red"
+
+    q = httpx.QueryParams({"color": Color.GREEN})
+    assert q["color"] == "2"
+
+    assert str(Status.ACTIVE) != Status.ACTIVE.value
+    q = httpx.QueryParams({"status": Status.ACTIVE})
+    assert q["status"] == "active"
+
+    q = httpx.QueryParams({"level": [Level.HIGH]})
+    assert q["level"] == "3"
+
+    q = httpx.QueryParams().add("status", Status.ACTIVE)
+    assert str(q) == "status=active"
+
+
+def test_queryparams_bytes_keys_and_values():
+    q = httpx.QueryParams({b"key": b"value"})
+    assert str(q) == "key=value"
+
+    q = httpx.QueryParams({"a": [b"1", "2"]})
+    assert q.get_list("a") == ["1", "2"]
+
+    q = httpx.QueryParams([(b"caf\xc3\xa9", b"na\xc3\xafve")])
+    assert q["café"] == "naïve"
+
+    with pytest.raises(UnicodeDecodeError):
+        httpx.QueryParams({b"a": b"\xff"})
+
+
+def test_queryparams_drop_none():
+    q = httpx.QueryParams({"a": None, "b": "1"})
+    assert str(q) == "a=&b=1"
+
+    q = httpx.QueryParams.with_options({"a": None, "b": "1"}, drop_none=True)
+    assert str(q) == "b=1"
+    assert "a" not in q
+
+    q = httpx.QueryParams.with_options({"a": ["1", None, "2"]}, drop_none=True)
+    assert q.get_list("a") == ["1", "2"]
+
+    q = httpx.QueryParams.with_options([("a", None), ("b", "2")], drop_none=True)
+    assert q.multi_items() == [("b", "2")]
+
+    q = httpx.QueryParams.with_options({"filter": None, "page": 2}, drop_none=True)
+    assert str(q) == "page=2"
+
+
+@pytest.mark.parametrize(
+    "bool_format,expected",
+    [("lower", "a=true&b=false"), ("python", "a=True&b=False"), ("int", "a=1&b=0")],
+)
+def test_queryparams_bool_format(bool_format, expected):
+    q = httpx.QueryParams.with_options(
+        {"a": True, "b": [False]}, bool_format=bool_format
+    )
+    assert str(q) == expected
+
+    q = httpx.QueryParams().set("a", True, bool_format=bool_format)
+    q = q.add("b", False, bool_format=bool_format)
+    assert str(q) == expected
+
+
+@pytest.mark.parametrize(
+    "bool_format,expected",
+    [("lower", "true=1&false=0"), ("python", "True=1&False=0"), ("int", "1=1&0=0")],
+)
+def test_queryparams_bool_format_applies_to_keys(bool_format, expected):
+    q = httpx.QueryParams.with_options({True: 1, False: 0}, bool_format=bool_format)
+    assert str(q) == expected
+
+    q = httpx.QueryParams.with_options([(True, 1), (False, 0)], bool_format=bool_format)
+    assert str(q) == expected
+
+
+def test_queryparams_bool_format_does_not_affect_parsing():
+    q = httpx.QueryParams.with_options("a=true", bool_format="int")
+    assert q["a"] == "true"
+
+
+def test_queryparams_invalid_bool_format():
+    with pytest.raises(ValueError):
+        httpx.QueryParams.with_options({"a": True}, bool_format="yes")
+
+
+@pytest.mark.parametrize(
+    "kwargs,expected",
+    [
+        ({"drop_none": "1"}, "drop_none=1"),
+        ({"bool_format": "x"}, "bool_format=x"),
+        ({"max_params": 1, "max_length": 10}, "max_params=1&max_length=10"),
+        ({"a": None, "drop_none": True}, "a=&drop_none=true"),
+    ],
+)
+def test_queryparams_option_names_are_plain_keyword_params(kwargs, expected):
+    assert str(httpx.QueryParams(**kwargs)) == expected
+    assert str(httpx.QueryParams({"b": "2"}, **kwargs)) == "b=2&" + expected
+
+
+def test_queryparams_with_options_is_keyword_only():
+    assert httpx.QueryParams.with_options() == httpx.QueryParams()
+    with pytest.raises(TypeError):
+        httpx.QueryParams.with_options({"a": None}, True)
+    with pytest.raises(TypeError):
+        httpx.QueryParams.with_options(params={"a": None})
+
+
+def test_queryparams_non_string_keys():
+    q = httpx.QueryParams({1: "a", False: "b"})
+    assert q.multi_items() == [("1", "a"), ("false", "b")]
+
+    q = httpx.QueryParams([(2, "x")])
+    assert q["2"] == "x"
+
+    with pytest.raises(TypeError, match="QueryParams keys must be str, got float: 1.5"):
+        httpx.QueryParams({1.5: "a"})
+
+    with pytest.raises(TypeError, match="QueryParams keys must be str, got NoneType"):
+        httpx.QueryParams([(None, "a")])
+
+
+@pytest.mark.parametrize(
+    "value,type_name",
+    [([["nested"]], "list"), ([("a", "b")], "tuple"), ({"b": 1}, "dict")],
+)
+def test_queryparams_nested_values(value, type_name):
+    with pytest.raises(TypeError, match=f"'a' has a nested {type_name} value"):
+        httpx.QueryParams({"a": value})
+
+
+def test_queryparams_nested_values_in_set_and_add():
+    q = httpx.QueryParams()
+    with pytest.raises(TypeError, match="'a' has a nested list value"):
+        q.set("a", [1])
+    with pytest.raises(TypeError, match="'b' has a nested dict value"):
+        q.add("b", {})
+
+
+def test_queryparams_max_length():
+    q = httpx.QueryParams.with_options("a=1&b=2", max_length=7)
+    assert len(q) == 2
+
+    with pytest.raises(httpx.QueryParamsTooLarge):
+        httpx.QueryParams.with_options("a=1&b=2", max_length=6)
+
+    with pytest.raises(httpx.QueryParamsTooLarge):
+        httpx.QueryParams.with_options(b"a=1" * 10_000, max_length=1024)
+
+
+def test_queryparams_max_params():
+    q = httpx.QueryParams.with_options("a=1&a=2&b=3", max_params=3)
+    assert q.multi_items() == [("a", "1"), ("a", "2"), ("b", "3")]
+
+    with pytest.raises(httpx.QueryParamsTooLarge):
+        httpx.QueryParams.with_options("a=1&" * 1_000_000, max_params=1000)
+
+    with pytest.raises(httpx.QueryParamsTooLarge):
+        httpx.QueryParams.with_options({"a": list(range(10)), "b": 1}, max_params=10)
+
+    with pytest.raises(httpx.QueryParamsTooLarge):
+        params = [("a", str(i)) for i in range(100)]
+        httpx.QueryParams.with_options(params, max_params=99)
+
+    with pytest.raises(httpx.QueryParamsTooLarge):
+        httpx.QueryParams.with_options({"a": 1, "b": 2}, max_params=1)
+
+
+@pytest.mark.parametrize("source", ["a=1&" * 1000, b"a=1&" * 1000])
+def test_queryparams_max_params_fails_fast(source):
+    with pytest.raises(httpx.QueryParamsTooLarge):
+        httpx.QueryParams.with_options(source, max_params=100)
+
+
+def test_queryparams_max_params_stops_consuming_pairs():
+    consumed = []
+
+    def pairs():
+        while True:
+            consumed.append(None)
+            yield ("a", "1")
+
+    # The generator never ends, so this only returns if parsing stops at the limit.
+    with pytest.raises(httpx.QueryParamsTooLarge):
+        httpx.QueryParams.with_options(pairs(), max_params=100)
+    assert len(consumed) == 101
+
+
+def test_queryparams_limits_default_to_unlimited():
+    q = httpx.QueryParams("a=1&" * 100_000)
+    assert q.get_list("a") == ["1"] * 100_000
+
+
+@pytest.mark.parametrize(
+    "source",
+    [
+        "a=%2f",
+        "a=%2F",
+        "%7e=%7E",
+        "a=%e2%82%ac&b=%E2%82%AC",
+        "a=%zz&b=%2",
+        "x=1&y=%3d%3D&z",
+    ],
+)
+def test_queryparams_canonical_hex_serialization(source):
+    q = httpx.QueryParams(source)
+    rendered = str(q)
+    assert rendered == str(httpx.QueryParams(rendered))
+    assert all(
+        rendered[i + 1 : i + 3].upper() == rendered[i + 1 : i + 3]
+        for i, c in enumerate(rendered)
+        if c == "%"
+    )
+
+
+def test_queryparams_hex_case_is_insignificant():
+    lower = httpx.QueryParams("a=%2f")
+    upper = httpx.QueryParams("a=%2F")
+    assert str(lower) == str(upper) == "a=%2F"
+    assert lower == upper
+    assert hash(lower) == hash(upper)
+
+
+@pytest.mark.parametrize(
+    "source",
+    [
+        {"city": "München"},
+        {"straße": "groß", "日本": "東京"},
+        {"a&b": "c=d", "e%f": "100%", "g+h": "i j"},
+        {"emoji": "🎉🚀", "🔑": "value"},
+        {"": "", "?": "#", "empty": ""},
+        [("a", "1"), ("b", "2"), ("a", "%2F")],
+        "city=M%C3%BCnchen&q=a+b%2Bc",
+    ],
+)
+def test_queryparams_string_round_trip(source):
+    q = httpx.QueryParams(source)
+    assert httpx.QueryParams(str(q)) == q
+    assert str(httpx.QueryParams(str(q))) == str(q)
+
+
+def test_queryparams_non_ascii_round_trip():
+    q = httpx.QueryParams("city=München")
+    assert str(q) == "city=M%C3%BCnchen"
+    assert httpx.QueryParams(str(q)) == q
+    assert httpx.QueryParams(str(q))["city"] == "München"
+
+
+@pytest.mark.parametrize(
+    "source",
+    ["https://example.com/path?a=1&b=2", "http://example.com", "?ftp://host/file"],
+)
+def test_queryparams_rejects_full_url(source):
+    with pytest.raises(httpx.InvalidURL, match="Expected a query string"):
+        httpx.QueryParams(source)
+
+
+def test_queryparams_url_in_value_is_allowed():
+    q = httpx.QueryParams("next=https://example.com/path?a=1&b=2")
+    assert q["next"] == "https://example.com/path?a=1"
+    assert q["b"] == "2"
+
+
+def test_queryparams_kwargs_merged_over_positional():
+    q = httpx.QueryParams({"a": "1"}, b="2")
+    assert q.multi_items() == [("a", "1"), ("b", "2")]
+
+    q = httpx.QueryParams("a=1&a=2&c=3", a="x")
+    assert q.multi_items() == [("a", "x"), ("c", "3")]
+
+    q = httpx.QueryParams([("a", "1")], a=["x", "y"], b=None)
+    assert q.multi_items() == [("a", "x"), ("a", "y"), ("b", "")]
+
+    q = httpx.QueryParams(None, a="1")
+    assert str(q) == "a=1"
+
+
+def test_queryparam_set_list():
+    q = httpx.QueryParams("tag=x&page=1")
+    q = q.set_list("tag", ["a", "b"])
+    assert q.multi_items() == [("tag", "a"), ("tag", "b"), ("page", "1")]
+
+    q = q.set_list("new", (1, True))
+    assert q.multi_items()[-2:] == [("new", "1"), ("new", "true")]
+
+    q = q.set_list("tag", [])
+    assert "tag" not in q
+    assert list(q.keys()) == ["page", "new"]
+
+    with pytest.raises(TypeError):
+        q.set_list("tag", "abc")
+
+
+def test_queryparam_extend():
+    q = httpx.QueryParams("a=1&b=2")
+    q = q.extend([("c", 3), ("a", True), ("c", None)])
+    assert q.multi_items() == [
+        ("a", "1"),
+        ("a", "true"),
+        ("b", "2"),
+        ("c", "3"),
+        ("c", ""),
+    ]
+
+    q = httpx.QueryParams("a=1").extend({"a": ["2", "3"], "b": 4})
+    assert q.multi_items() == [("a", "1"), ("a", "2"), ("a", "3"), ("b", "4")]
+
+    original = httpx.QueryParams("a=1")
+    original.extend([("a", "2")])
+    assert original.multi_items() == [("a", "1")]
+
+
+def test_queryparam_remove_value():
+    q = httpx.QueryParams("tag=a&tag=b&tag=c&tag=b")
+    q = q.remove_value("tag", "b")
+    assert q.get_list("tag") == ["a", "c", "b"]
+
+    q = httpx.QueryParams("tag=a&page=1").remove_value("tag", "a")
+    assert "tag" not in q
+    assert list(q.keys()) == ["page"]
+
+    q = httpx.QueryParams("active=true").remove_value("active", True)
+    assert not q
+
+    q = httpx.QueryParams("tag=a")
+    assert q.remove_value("tag", "z") == q
+    assert q.remove_value("missing", "a") == q
+
+
+def test_queryparam_pop():
+    q = httpx.QueryParams("page_token=abc&page_token=def&size=10")
+    token, rest = q.pop("page_token")
+    assert token == "abc"
+    assert rest == httpx.QueryParams("size=10")
+    assert q.get_list("page_token") == ["abc", "def"]
+
+    value, rest = q.pop("missing", None)
+    assert value is None
+    assert rest == q
+
+    value, rest = q.pop("missing", "fallback")
+    assert value == "fallback"
+
+    value, rest = q.pop("missing", default=None)
+    assert value is None
+    assert rest == q
+
+    value, rest = q.pop("size", default="fallback")
+    assert value == "10"
+
+    with pytest.raises(KeyError):
+        q.pop("missing")
+
+    with pytest.raises(TypeError):
+        q.pop("size", 1, 2)
+
+
+def test_queryparam_sort():
+    q = httpx.QueryParams("b=2&a=1&b=0&A=3")
+    assert str(q.sort()) == "A=3&a=1&b=2&b=0"
+    assert str(q) == "b=2&b=0&a=1&A=3"
+
+    # Sorting is bytewise on the encoded keys, so "%C3%A9" sorts before "z".
+    q = httpx.QueryParams({"z": "1", "é": "2", "a b": "3"})
+    assert str(q.sort()) == "%C3%A9=2&a+b=3&z=1"
+    assert q.sort().sort() == q.sort()
+
+
+def test_queryparam_merge_append():
+    q = httpx.QueryParams("tag=a&page=1")
+    assert q.merge("tag=b") == httpx.QueryParams("tag=b&page=1")
+
+    merged = q.merge_append("tag=b&sort=asc")
+    assert merged.multi_items() == [
+        ("tag", "a"),
+        ("tag", "b"),
+        ("page", "1"),
+        ("sort", "asc"),
+    ]
+    assert q.merge_append() == q
+    assert q.merge_append({"tag": ["c", "d"]}).get_list("tag") == ["a", "c", "d"]
+
+
+def test_queryparam_or_operator():
+    q = httpx.QueryParams("a=1&page=1")
+    assert q | {"page": 2} == httpx.QueryParams("a=1&page=2")
+    assert q | httpx.QueryParams("b=2") == httpx.QueryParams("a=1&page=1&b=2")
+    assert q | [("a", "x")] == httpx.QueryParams("a=x&page=1")
+
+    merged = {"a": 0, "c": 3} | q
+    assert isinstance(merged, httpx.QueryParams)
+    assert merged.multi_items() == [("a", "1"), ("c", "3"), ("page", "1")]
+
+    with pytest.raises(TypeError):
+        q | 1
+
+    with pytest.raises(TypeError):
+        q | "b=2"
+
+
+def test_queryparam_without():
+    q = httpx.QueryParams("a=1&utm_source=x&b=2&b=3&state=s&utm_medium=y&c=4")
+    q = q.without(["utm_source", "utm_medium", "state", "missing"])
+    assert q.multi_items() == [("a", "1"), ("b", "2"), ("b", "3"), ("c", "4")]
+
+    assert httpx.QueryParams("ab=1&a=2").without("ab") == httpx.QueryParams("a=2")
+    assert httpx.QueryParams("a=1").without(k for k in ["a"]) == httpx.QueryParams()
+
+
+def test_queryparam_filter():
+    q = httpx.QueryParams("a=1&_internal_id=2&b=&c=3&c=")
+    q = q.filter(lambda key, value: value and not key.startswith("_internal"))
+    assert q.multi_items() == [("a", "1"), ("c", "3")]
+    assert q.keys() == ["a", "c"]
+
+    def fail(key, value):
+        raise RuntimeError(key)
+
+    with pytest.raises(RuntimeError):
+        q.filter(fail)
+
+
+def test_queryparam_rename_key():
+    q = httpx.QueryParams("a=0&q=rust&q=python&page=2")
+    renamed = q.rename_key("q", "query")
+    assert renamed.multi_items() == [("a", "0"), ("query", "rust"), ("query", "python"), ("page", "2")]
+
+    assert q.rename_key("missing", "other") == q
+    assert q.rename_key("q", "q") == q
+
+    with pytest.raises(ValueError):
+        q.rename_key("q", "page")
+
+
+def test_queryparam_setdefault():
+    q = httpx.QueryParams("a=123&a=456")
+    assert q.setdefault("a", "789").multi_items() == [("a", "123"), ("a", "456")]
+    assert q.setdefault("page", 1) == httpx.QueryParams("a=123&a=456&page=1")
+    assert q.setdefault("flag", True) == httpx.QueryParams("a=123&a=456&flag=true")
+
+    q = httpx.QueryParams({"a": ""})
+    assert q.setdefault("a", "value") == httpx.QueryParams({"a": ""})
+
+
+def test_queryparam_typed_getters():
+    q = httpx.QueryParams("page=2&page=3&lat=51.5&debug=Yes&off=0&name=abc")
+    assert q.get_int("page") == 2
+    assert q.get_int("missing") is None
+    assert q.get_int("missing", 10) == 10
+    assert q.get_float("lat") == 51.5
+    assert q.get_float("page") == 2.0
+    assert q.get_bool("debug") is True
+    assert q.get_bool("off") is False
+    assert q.get_bool("missing", True) is True
+
+    with pytest.raises(ValueError, match="'name' has value 'abc'"):
+        q.get_int("name")
+
+    with pytest.raises(ValueError, match="'name' has value 'abc'"):
+        q.get_float("name")
+
+    with pytest.raises(ValueError, match="'lat' has value '51.5'"):
+        q.get_bool("lat")
+
+
+@pytest.mark.parametrize(
+    "raw,expected",
+    [
+        ("12345678901234567890", 12345678901234567890),
+        ("-98765432109876543210987654321", -98765432109876543210987654321),
+        (str(2**63), 2**63),
+        (str(-(2**63)), -(2**63)),
+        ("1_000", 1000),
+        ("+7", 7),
+    ],
+)
+def test_queryparam_get_int_matches_python_int(raw, expected):
+    assert httpx.QueryParams({"a": raw}).get_int("a") == expected
+
+
+@pytest.mark.parametrize("raw", ["", "1.5", "1e3", "0x10", "1__0", "12345678901234567890a"])
+def test_queryparam_get_int_invalid(raw):
+    with pytest.raises(ValueError, match="'a' has value"):
+        httpx.QueryParams({"a": raw}).get_int("a")
+
+
+@pytest.mark.parametrize("raw", [" 1.5 ", "1_000.5", "+.5", "1e3", "-inf", "NaN"])
+def test_queryparam_get_float_matches_python_float(raw):
+    value = httpx.QueryParams({"a": raw}).get_float("a")
+    assert repr(value) == repr(float(raw))
+
+
+@pytest.mark.parametrize("raw", ["", "1,5", "0x10", "1__0.5", "infinite"])
+def test_queryparam_get_float_invalid(raw):
+    with pytest.raises(ValueError, match="'a' has value"):
+        httpx.QueryParams({"a": raw}).get_float("a")
+
+
+@pytest.mark.parametrize("raw", ["true", "TRUE", "1", "yes", "YES"])
+def test_queryparam_get_bool_true(raw):
+    assert httpx.QueryParams({"a": raw}).get_bool("a") is True
+
+
+@pytest.mark.parametrize("raw", ["false", "False", "0", "no", "No"])
+def test_queryparam_get_bool_false(raw):
+    assert httpx.QueryParams({"a": raw}).get_bool("a") is False
+
+
+def test_queryparam_get_last():
+    q = httpx.QueryParams({"a": ["1", "2", "3"], "b": "x", "empty": []})
+    assert q.get("a") == "1"
+    assert q.get("a", last=True) == "3"
+    assert q.get("b", last=True) == "x"
+    assert q.get("missing", "default", last=True) == "default"
+    assert q.get("empty", "default", last=True) == "default"
+    assert q["a"] == "1"
+
+
+def test_queryparam_multi_dict():
+    q = httpx.QueryParams({"b": ["1", "2"], "a": "x", "empty": []})
+    result = q.multi_dict()
+    assert result == {"b": ["1", "2"], "a": ["x"]}
+    assert list(result) == ["b", "a"]
+    assert httpx.QueryParams().multi_dict() == {}
+
+
+def test_queryparam_to_dict():
+    q = httpx.QueryParams({"b": ["1", "2"], "a": "x", "empty": []})
+    result = q.to_dict()
+    assert result == {"b": "1", "a": "x"}
+    assert list(result.items()) == q.items()
+
+
+def test_queryparam_iter_multi_items():
+    q = httpx.QueryParams({"a": ["1", "2"], "empty": [], "b": "3"})
+    it = q.iter_multi_items()
+    assert iter(it) is it
+    assert next(it) == ("a", "1")
+    assert list(it) == [("a", "2"), ("b", "3")]
+    assert list(q.iter_multi_items()) == q.multi_items()
+    assert list(httpx.QueryParams().iter_multi_items()) == []
+
+
+def test_queryparam_pairs():
+    q = httpx.QueryParams({"a": ["1", "2"], "empty": [], "b": "3"})
+    it = q.pairs()
+    assert iter(it) is it
+    assert list(it) == [("a", "1"), ("b", "3")]
+    assert list(q.pairs()) == q.items()
+    assert list(q) == ["a", "b"]
+
+
+def test_queryparam_reversed():
+    q = httpx.QueryParams("a=1&b=2&a=3&c=4")
+    assert list(reversed(q)) == ["c", "b", "a"]
+    assert list(reversed(httpx.QueryParams())) == []
+
+
+def test_queryparam_item_at():
+    q = httpx.QueryParams("a=1&b=2&a=3&c=4")
+    assert [q.item_at(i) for i in range(len(q))] == q.items()
+    assert q.item_at(-1) == ("c", "4")
+    assert q.item_at(-3) == ("a", "1")
+
+    with pytest.raises(IndexError):
+        q.item_at(3)
+
+    with pytest.raises(IndexError):
+        q.item_at(-4)
+
+    with pytest.raises(IndexError):
+        httpx.QueryParams().item_at(0)
+
+
+def test_queryparam_contains_pair():
+    q = httpx.QueryParams("tag=rust&tag=python&page=2")
+    assert "tag" in q
+    assert ("tag", "rust") in q
+    assert ("tag", "python") in q
+    assert ("page", 2) in q
+    assert ("tag", "go") not in q
+    assert ("missing", "rust") not in q
+
+    with pytest.raises(TypeError):
+        1 in q
+
+    with pytest.raises(TypeError):
+        ("tag", "rust", "python") in q
+
+
+def test_queryparam_eq_literal_forms():
+    q = httpx.QueryParams("a=1&b=2&b=3")
+    assert q == "a=1&b=2&b=3"
+    assert q == "b=2&b=3&a=1"
+    assert q == b"a=1&b=2&b=3"
+    assert q == {"a": "1", "b": ["2", "3"]}
+    assert q == [("a", "1"), ("b", "2"), ("b", "3")]
+    assert "a=1&b=2&b=3" == q
+    assert q != "a=1"
+    assert q != {"a": "1"}
+    assert not (q != "a=1&b=2&b=3")
+
+
+@pytest.mark.parametrize("other", [1, None, ("a", "1"), object(), [1, 2]])
+def test_queryparam_eq_unsupported(other):
+    q = httpx.QueryParams("a=1")
+    assert q.__eq__(other) is NotImplemented
+    assert q != other
+    assert not (q == other)
+
+
+@pytest.mark.parametrize(
+    "value",
+    ["it's", 'say "hi"', "back\\slash", "new\nline", "tab\there", "\x00", "naïve"],
+)
+def test_queryparam_repr_escaping(value):
+    q = httpx.QueryParams().set("key'\"", value)
+    text = repr(q)
+    assert text.startswith("QueryParams(") and text.endswith(")")
+    assert "\n" not in text
+    assert eval(text, {"QueryParams": httpx.QueryParams}) == q
+
+
+@pytest.mark.parametrize(
+    "params",
+    [
+        {"empty": []},
+        {"empty": ()},
+        {"empty": set()},
+        {"a": "1", "empty": []},
+        {"empty": [None]},
+    ],
+)
+def test_queryparam_empty_value_lists_are_dropped(params):
+    q = httpx.QueryParams.with_options(params, drop_none=True)
+    assert "empty" not in q
+    assert len(q) == len(q.keys()) == len(q.items())
+    assert bool(q) == bool(q.items())
+
+    q = q.add("other", "x").remove("other").merge({"empty": []}).set_list("x", [])
+    assert "empty" not in q
+    assert len(q) == len(q.keys()) == len(q.items())
+    assert bool(q) == bool(q.items())
+
+
+def test_queryparam_views():
+    q = httpx.QueryParams("a=1&a=2&b=3")
+
+    keys = q.keys_view()
+    assert len(keys) == 2
+    assert "a" in keys
+    assert "c" not in keys
+    assert 1 not in keys
+    assert list(keys) == q.keys()
+
+    values = q.values_view()
+    assert len(values) == 2
+    assert "1" in values
+    assert "2" not in values
+    assert list(values) == q.values()
+
+    items = q.items_view()
+    assert len(items) == 2
+    assert ("a", "1") in items
+    assert ("a", "2") not in items
+    assert "a" not in items
+    assert list(items) == q.items()
+
+    empty = httpx.QueryParams()
+    assert len(empty.keys_view()) == len(empty.values_view()) == len(empty.items_view()) == 0
+
+
+def test_queryparam_iter_values():
+    q = httpx.QueryParams("a=1&a=2&b=3&c=4")
+    it = q.iter_values()
+    assert iter(it) is it
+    assert next(it) == "1"
+
+    q = q.set("b", "replaced")
+    assert list(it) == ["3", "4"]
+    assert list(q.iter_values()) == q.values()
+    assert list(httpx.QueryParams().iter_values()) == []
+
+
+def test_queryparam_builder():
+    builder = httpx.QueryParams.builder()
+    for page in range(3):
+        builder.add("page", page)
+    builder.set("sort", "asc").set("flag", True).set_list("tag", ["a", "b"])
+    builder["cursor"] = "abc"
+    del builder["sort"]
+    builder.remove("missing").update({"flag": "false"})
+
+    assert len(builder) == 4
+    assert "page" in builder
+    assert "sort" not in builder
+
+    q = builder.build()
+    assert isinstance(q, httpx.QueryParams)
+    assert str(q) == "page=0&page=1&page=2&flag=false&tag=a&tag=b&cursor=abc"
+    assert len(builder) == 0
+
+    with pytest.raises(KeyError):
+        del builder["missing"]
+
+
+def test_queryparam_to_builder():
+    q = httpx.QueryParams("a=1&b=2")
+    builder = q.to_builder()
+    builder["a"] = "changed"
+    assert builder.build() == httpx.QueryParams("a=changed&b=2")
+    assert q == httpx.QueryParams("a=1&b=2")
+
+    assert httpx.QueryParamsBuilder("x=1").build() == httpx.QueryParams("x=1")
+
+
+def test_queryparam_copy_with():
+    q = httpx.QueryParams("a=1&page=2&page=5&cursor=abc&z=9")
+    result = q.copy_with(page=3, cursor=None, new=True, missing=None)
+    assert result.multi_items() == [("a", "1"), ("page", "3"), ("z", "9"), ("new", "true")]
+    assert q.copy_with() == q
+
+
+def test_queryparam_encode():
+    q = httpx.QueryParams({"path": "/a b", "t": "12:00", "pct": "100%"})
+    assert q.encode() == "path=%2Fa%20b&t=12%3A00&pct=100%25"
+    assert q.encode(safe="/:") == "path=/a%20b&t=12:00&pct=100%25"
+    assert q.encode(safe="/:", quote_via="plus") == "path=/a+b&t=12:00&pct=100%25"
+    assert httpx.QueryParams({"a": "1+2"}).encode(quote_via="plus") == "a=1%2B2"
+    assert str(q) == "path=%2Fa+b&t=12%3A00&pct=100%25"
+
+    with pytest.raises(ValueError):
+        q.encode(quote_via="other")
+
+    assert q.encode(safe=b"/:") == q.encode(safe="/:")
+    with pytest.raises(ValueError):
+        q.encode(safe="é")
+
+
+def test_queryparam_format():
+    q = httpx.QueryParams({"a": ["1", "2"], "b": "x y"})
+    assert f"{q}" == str(q)
+    assert f"{q:;}" == "a=1;a=2;b=x+y"
+    assert format(q, ",") == "a=1,a=2,b=x+y"
+    assert f"{q:pretty}" == "a=1\na=2\nb=x+y"
+
+    for spec in ["x", "ab", ";;", "PRETTY"]:
+        with pytest.raises(ValueError):
+            format(q, spec)
+
+
+def test_queryparam_total_and_count():
+    q = httpx.QueryParams("a=1&a=2&a=3&b=4&c=")
+    assert len(q) == 3
+    assert q.total() == 5
+    assert q.count("a") == 3
+    assert q.count("b") == 1
+    assert q.count("c") == 1
+    assert q.count("missing") == 0
+    assert httpx.QueryParams().total() == 0
+
+
+def test_queryparam_get_prefixed():
+    q = httpx.QueryParams("filter.name=a&sort.field=name&filter.tag=x&filter.tag=y&filter.age=3")
+    assert q.get_prefixed("filter.").multi_items() == [
+        ("filter.name", "a"),
+        ("filter.tag", "x"),
+        ("filter.tag", "y"),
+        ("filter.age", "3"),
+    ]
+    assert q.get_prefixed("filter.", strip=True).multi_items() == [
+        ("name", "a"),
+        ("tag", "x"),
+        ("tag", "y"),
+        ("age", "3"),
+    ]
+    assert q.get_prefixed("").multi_items() == q.multi_items()
+    assert q.get_prefixed("missing.") == httpx.QueryParams()
+
+
+def test_queryparam_exists():
+    q = httpx.QueryParams("tag=rust&tag=python&active=true&page=2")
+    assert q.exists("tag", "rust")
+    assert q.exists("tag", "python")
+    assert q.exists("active", True)
+    assert q.exists("page", 2)
+    assert not q.exists("tag", "go")
+    assert not q.exists("active", False)
+    assert not q.exists("missing", "rust")
+
+
+def test_queryparam_hash_ignores_order():
+    a = httpx.QueryParams("a=1&b=2&a=3")
+    b = httpx.QueryParams("b=2&a=1&a=3")
+    assert a == b
+    assert hash(a) == hash(b)
+    assert len({a, b}) == 1
+    assert {a: "value"}[b] == "value"
+
+
+@pytest.mark.parametrize(
+    "encoded, decoded",
+    [
+        ("q=a%20b", {"q": "a b"}),
+        ("q=a+b", {"q": "a b"}),
+        ("path=%2fx", {"path": "/x"}),
+        ("path=%2Fx", {"path": "/x"}),
+        ("k%C3%A9y=caf%C3%A9", {"kéy": "café"}),
+    ],
+)
+def test_queryparam_encoded_and_decoded_equal(encoded, decoded):
+    a = httpx.QueryParams(encoded)
+    b = httpx.QueryParams(decoded)
+    assert a == b
+    assert hash(a) == hash(b)
+
+
+def test_queryparam_encoding_variants_equal():
+    variants = [httpx.QueryParams(s) for s in ["q=a%20b&p=%2f", "q=a+b&p=%2F", "p=/&q=a b"]]
+    assert len(set(variants)) == 1
+
+
+@pytest.mark.parametrize(
+    "query, expected",
+    [
+        # Vectors from the AWS Signature Version 4 test suite.
+        ("Version=2010-05-08&Action=ListUsers", "Action=ListUsers&Version=2010-05-08"),
+        ("Param2=value2&Param1=value1", "Param1=value1&Param2=value2"),
+        ("Param1=value2&Param1=Value1", "Param1=Value1&Param1=value2"),
+        (
+            "-._~0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz"
+            "=-._~0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz",
+            "-._~0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz"
+            "=-._~0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz",
+        ),
+        ("q=a+b&p=%2f&e=%C3%A9", "e=%C3%A9&p=%2F&q=a%20b"),
+        ("b=*&a=!", "a=%21&b=%2A"),
+    ],
+)
+def test_queryparam_canonical(query, expected):
+    assert httpx.QueryParams(query).canonical() == expected
+
+
+def test_queryparam_rendering_is_not_shared_with_copies():
+    q = httpx.QueryParams("a=1&b=2")
+    assert str(q) == "a=1&b=2"
+    assert str(q) == "a=1&b=2"
+
+    assert str(q.set("a", "3")) == "a=3&b=2"
+    assert str(q.add("c", "4")) == "a=1&b=2&c=4"
+    assert str(q.remove("b")) == "a=1"
+    assert str(q.copy_with(b=None)) == "a=1"
+    assert str(q.merge({"b": "5"})) == "a=1&b=5"
+    assert repr(q) == "QueryParams('a=1&b=2')"
+
+
+def test_queryparam_hash_is_not_shared_with_copies():
+    q = httpx.QueryParams("a=1&b=2")
+    assert hash(q) == hash(q)
+    assert hash(q) == hash(httpx.QueryParams("b=2&a=1"))
+
+    changed = q.set("a", "3")
+    assert hash(changed) == hash(httpx.QueryParams("a=3&b=2"))
+    assert hash(q.set("a", "1")) == hash(q)
+
+
+@pytest.mark.parametrize(
+    "query, expected",
+    [
+        ("a", [("a", "")]),
+        ("a=", [("a", "")]),
+        ("=b", [("", "b")]),
+        ("a=b=c", [("a", "b=c")]),
+        ("a==", [("a", "=")]),
+        ("a&&b=1&", [("a", ""), ("b", "1")]),
+        ("&&&", []),
+        ("a=1&a&a=", [("a", "1"), ("a", ""), ("a", "")]),
+    ],
+)
+def test_queryparam_parse_edge_cases(query, expected):
+    assert httpx.QueryParams(query).multi_items() == expected
+
+
+def test_queryparam_parse_many_pairs():
+    query = "&".join(f"k{i % 100}=v{i}" for i in range(5000))
+    q = httpx.QueryParams(query)
+    assert len(q) == 100
+    assert q.total() == 5000
+    assert q.get_list("k7")[:2] == ["v7", "v107"]
+    assert str(q) == "&".join(f"k{i % 100}=v{i}" for i in sorted(range(5000), key=lambda i: i % 100))
+
+
+def test_queryparam_exact_and_subclassed_values():
+    class MyStr(str):
+        pass
+
+    class MyInt(int):
+        def __str__(self):
+            return "custom"
+
+    q = httpx.QueryParams(
+        {"s": "text", "b": True, "i": -42, "big": 2**80, "sub": MyStr("sub"), "myint": MyInt(3)}
+    )
+    assert q.multi_items() == [
+        ("s", "text"),
+        ("b", "true"),
+        ("i", "-42"),
+        ("big", str(2**80)),
+        ("sub", "sub"),
+        ("myint", "custom"),
+    ]
+
+    q = httpx.QueryParams({f"key{i}": f"value{i}" for i in range(1000)})
+    assert q.total() == 1000
+    assert q["key999"] == "value999"
+
+
+def test_queryparam_parse_large_inputs_on_threads():
+    from concurrent.futures import ThreadPoolExecutor
+
+    queries = ["&".join(f"t{t}k{i}=v{i}" for i in range(5000)) for t in range(8)]
+
+    def roundtrip(query):
+        q = httpx.QueryParams(query)
+        return len(q), str(q) == query
+
+    with ThreadPoolExecutor(max_workers=8) as executor:
+        results = list(executor.map(roundtrip, queries))
+
+    assert results == [(5000, True)] * 8
+
+
+@pytest.mark.parametrize("encode", [False, True])
+def test_queryparam_parse_releases_the_gil(encode):
+    import sys
+    import threading
+    import time
+
+    query = "&".join(f"k{i}=v%20{i}" for i in range(20_000))
+    source = query.encode("ascii") if encode else query
+    ticks = [0]
+    started = threading.Event()
+    done = threading.Event()
+
+    def count():
+        started.set()
+        while not done.is_set():
+            ticks[0] += 1
+            time.sleep(0)
+
+    # With a long switch interval the interpreter never preempts this thread, so
+    # the counter can only advance during the parse if the parse lets go of the GIL.
+    interval = sys.getswitchinterval()
+    sys.setswitchinterval(1000)
+    thread = threading.Thread(target=count)
+    try:
+        thread.start()
+        started.wait()
+        before = ticks[0]
+        httpx.QueryParams(source)
+        after = ticks[0]
+    finally:
+        done.set()
+        thread.join()
+        sys.setswitchinterval(interval)
+
+    assert after > before
+
+
+def test_queryparam_urlencode_bytes():
+    q = httpx.QueryParams({"k y": "a+b/c é\x00~-._*"})
+    assert str(q) == "k+y=a%2Bb%2Fc+%C3%A9%00~-._%2A"
+    assert httpx.QueryParams({"": ""}).serialize(";") == "="
+    assert httpx.QueryParams([("a", "1"), ("b", "2")]).serialize("; ") == "a=1; b=2"
+
+
+@pytest.mark.parametrize(
+    "left, right, equal",
+    [
+        ("a=1&b=2", "b=2&a=1", True),
+        ("a=1&a=2", "a=2&a=1", True),
+        ("a=1&a=2&b=3", "b=3&a=2&a=1", True),
+        ("a=1", "a=1&b=2", False),
+        ("a=1&a=2", "a=1&b=2", False),
+        ("a=1&a=1", "a=1&a=2", False),
+        ("a=1&b=2", "a=1&c=2", False),
+        ("a=1&a=2&b=3", "a=1&b=2&b=3", False),
+        ("", "", True),
+    ],
+)
+def test_queryparam_eq_shapes(left, right, equal):
+    assert (httpx.QueryParams(left) == httpx.QueryParams(right)) is equal
+    assert (httpx.QueryParams(right) == httpx.QueryParams(left)) is equal
+
+
+def test_queryparam_merge_large():
+    left = httpx.QueryParams({f"k{i}": str(i) for i in range(500)})
+    right = httpx.QueryParams({f"k{i}": "r" for i in range(250, 750)})
+
+    merged = left.merge(right)
+    assert len(merged) == 750
+    assert merged.keys() == [f"k{i}" for i in range(750)]
+    assert merged["k0"] == "0"
+    assert merged["k300"] == "r"
+
+    appended = left.merge_append(right)
+    assert appended.total() == 1000
+    assert appended.get_list("k300") == ["300", "r"]
+
+
+def test_queryparam_common_keys():
+    pages = [httpx.QueryParams({"page": i, "per_page": 20, "sort": "id"}) for i in range(10_000)]
+    assert len(set(pages)) == 10_000
+    assert pages[5] == httpx.QueryParams("page=5&per_page=20&sort=id")
+    assert pages[5].rename_key("sort", "order") == httpx.QueryParams("page=5&per_page=20&order=id")
+    assert [page["page"] for page in pages[:3]] == ["0", "1", "2"]
+
+
+@pytest.mark.parametrize(
+    "query",
+    ["", "?", "?a=1", "a=1&b=&c&&=d", "a=b=c", "a+b=c%20d", "%E2%9C%93=%zz", "café=naïve&x=1"],
+)
+def test_queryparams_bytes_matches_str(query):
+    from_bytes = httpx.QueryParams(query.encode("utf-8"))
+    from_str = httpx.QueryParams(query)
+    assert from_bytes.multi_items() == from_str.multi_items()
+
+
+def test_queryparams_bytes_errors():
+    with pytest.raises(UnicodeDecodeError) as exc_info:
+        httpx.QueryParams(b"?a=1&b=\xff")
+    assert exc_info.value.start == 7
+
+    with pytest.raises(httpx.InvalidURL):
+        httpx.QueryParams(b"https://example.com/?a=1")
+
+    with pytest.raises(httpx.QueryParamsTooLarge):
+        httpx.QueryParams.with_options(b"a=1&b=2&c=3", max_params=2)
+
+
+def test_queryparams_large_bytes():
+    # Large enough to be parsed without holding the GIL.
+    query = "&".join(f"k{i}=caf%C3%A9+{i}" for i in range(5000))
+    q = httpx.QueryParams(query.encode("utf-8"))
+    assert q.multi_items() == httpx.QueryParams(query).multi_items()
+
+    with pytest.raises(UnicodeDecodeError) as exc_info:
+        httpx.QueryParams(query.encode("utf-8") + b"&b=\xff")
+    assert exc_info.value.start == len(query) + 3
+
+    with pytest.raises(httpx.InvalidURL):
+        httpx.QueryParams(b"https://example.com/?" + query.encode("utf-8"))
+
+    with pytest.raises(httpx.QueryParamsTooLarge):
+        httpx.QueryParams.with_options(query.encode("utf-8"), max_params=4999)
+
+
+def test_queryparam_multi_items_types():
+    q = httpx.QueryParams("a=1&a=2&b=é")
+    items = q.multi_items()
+    assert type(items) is list
+    assert items == [("a", "1"), ("a", "2"), ("b", "é")]
+    assert all(type(item) is tuple and len(item) == 2 for item in items)
+    assert httpx.QueryParams().multi_items() == []
+
+
+def test_queryparams_copies_without_changes():
+    q = httpx.QueryParams("a=1&b=2&a=3")
+
+    copied = httpx.QueryParams(q)
+    assert copied == q
+    assert hash(copied) == hash(q)
+    assert q.merge(None) == q
+    assert q.merge({}) == q
+    assert q.merge("") == q
+
+    changed = copied.set("a", "4")
+    assert str(changed) == "a=4&b=2"
+    assert str(copied) == "a=1&a=3&b=2"
+    assert str(q) == "a=1&a=3&b=2"
+
+
+@pytest.mark.parametrize(
+    "query",
+    ["", "a=1", "a=1&b=2&a=3", "b=2&a=1&b=3", "name=J%C3%BCrgen&city=%E6%9D%B1%E4%BA%AC"],
+)
+def test_queryparams_pickle(query):
+    import pickle
+
+    q = httpx.QueryParams(query)
+    restored = pickle.loads(pickle.dumps(q))
+    assert restored == q
+    assert str(restored) == str(q)
+    assert restored.multi_items() == q.multi_items()
+
+
+def test_queryparams_copy():
+    import copy
+
+    q = httpx.QueryParams("a=1&b=2&a=3")
+    assert copy.copy(q) == q
+    assert hash(copy.copy(q)) == hash(q)
+
+    data = {"params": q, "nested": [q]}
+    copied = copy.deepcopy(data)
+    assert copied == data
+    assert copied["params"] == q
+    assert hash(copied["params"]) == hash(q)
+    assert copied["nested"][0].multi_items() == q.multi_items()
+
+
+@pytest.mark.parametrize(
+    "query", ["", "a=1", "a=1&b=2&a=3", "b=2&a=1&b=3", "name=J%C3%BCrgen"]
+)
+def test_queryparams_json_round_trip(query):
+    q = httpx.QueryParams(query)
+    restored = httpx.QueryParams.from_json(q.json())
+    assert restored == q
+    assert restored.multi_items() == q.multi_items()
+
+
+def test_queryparams_json():
+    q = httpx.QueryParams("a=1&b=2&a=3")
+    assert q.json() == '[["a", "1"], ["a", "3"], ["b", "2"]]'
+
+    with pytest.raises(ValueError):
+        httpx.QueryParams.from_json('{"a": "1"}')
+
+
+PARSE_QS_FIXTURES = [
+    "",
+    "a=1",
+    "a=1&a=2&b=3",
+    "b=2&a=1&b=3",
+    "q=hello+world&empty=",
+    "name=J%C3%BCrgen&tags=x&tags=y&tags=z",
+]
+
+
+@pytest.mark.parametrize("query", PARSE_QS_FIXTURES)
+def test_queryparams_parse_qs(query):
+    from urllib.parse import parse_qs
+
+    expected = parse_qs(query, keep_blank_values=True)
+    q = httpx.QueryParams(query)
+    assert q.to_parse_qs() == expected
+    assert httpx.QueryParams.from_parse_qs(expected) == q
+    assert httpx.QueryParams.from_parse_qs(expected).to_parse_qs() == expected
+
+
+@pytest.mark.parametrize("query", PARSE_QS_FIXTURES)
+def test_queryparams_parse_qsl(query):
+    from urllib.parse import parse_qsl
+
+    expected = parse_qsl(query, keep_blank_values=True)
+    q = httpx.QueryParams(query)
+    # Repeated keys come back grouped at their first position.
+    keys = dict.fromkeys(key for key, _ in expected)
+    grouped = [(key, value) for k in keys for key, value in expected if key == k]
+    assert q.to_parse_qsl() == grouped
+    assert httpx.QueryParams.from_parse_qsl(expected) == q
+
+
+def test_queryparams_to_parse_qsl_groups_interleaved_keys():
+    from urllib.parse import parse_qsl
+
+    query = "b=2&a=1&b=3"
+    assert parse_qsl(query) == [("b", "2"), ("a", "1"), ("b", "3")]
+    assert httpx.QueryParams(query).to_parse_qsl() == [
+        ("b", "2"),
+        ("b", "3"),
+        ("a", "1"),
+    ]
+
+
+def test_queryparams_from_parse_qs_preserves_value_order():
+    q = httpx.QueryParams.from_parse_qs({"a": ["3", "1", "2"]})
+    assert q.get_list("a") == ["3", "1", "2"]
+    assert str(q) == "a=3&a=1&a=2"
+
+
+def test_queryparams_parse_qs_errors():
+    with pytest.raises(TypeError):
+        httpx.QueryParams.from_parse_qs([("a", "1")])  # type: ignore
+    with pytest.raises(TypeError):
+        httpx.QueryParams.from_parse_qsl({"a": ["1"]})  # type: ignore
+
+
+def test_queryparams_sizeof_grows_with_params():
+    import sys
+
+    def size(count):
+        return sys.getsizeof(httpx.QueryParams({f"key{i}": "x" * 32 for i in range(count)}))
+
+    empty = sys.getsizeof(httpx.QueryParams())
+    small, large = size(100), size(1000)
+    assert empty < small < large
+    per_param = (large - small) / 900
+    assert per_param >= 32
+    assert (small - empty) / 100 == pytest.approx(per_param, rel=0.5)
+
+
+def test_queryparams_from_string():
+    assert httpx.QueryParams.from_string("a=1&b=2") == httpx.QueryParams("a=1&b=2")
+
+    with pytest.raises(TypeError):
+        httpx.QueryParams.from_string(b"a=1")  # type: ignore
+    with pytest.raises(TypeError):
+        httpx.QueryParams.from_string({"a": "1"})  # type: ignore
+
+
+def test_queryparams_from_bytes_classmethod():
+    assert httpx.QueryParams.from_bytes(b"a=1&b=2") == httpx.QueryParams("a=1&b=2")
+    q = httpx.QueryParams.from_bytes("a=é".encode("latin-1"), encoding="latin-1")
+    assert q["a"] == "é"
+
+    with pytest.raises(TypeError):
+        httpx.QueryParams.from_bytes("a=1")  # type: ignore
+    with pytest.raises(UnicodeDecodeError):
+        httpx.QueryParams.from_bytes(b"a=\xff")
+    with pytest.raises(LookupError):
+        httpx.QueryParams.from_bytes(b"a=1", encoding="not-a-codec")
+
+
+@pytest.mark.parametrize(
+    "data,encoding,expected",
+    [
+        (b"a=%E9", "latin-1", [("a", "é")]),
+        (b"%E9=caf%E9+%FC", "iso-8859-1", [("é", "café ü")]),
+        (b"q=%93%8C%8B%9E", "shift_jis", [("q", "東京")]),
+        ("q=東京&r=%93%8C".encode("shift_jis"), "shift_jis", [("q", "東京"), ("r", "東")]),
+        (b"a=%E9", "utf-8", [("a", "\ufffd")]),
+    ],
+)
+def test_queryparams_from_bytes_decodes_escapes_with_encoding(data, encoding, expected):
+    q = httpx.QueryParams.from_bytes(data, encoding=encoding)
+    assert q.multi_items() == expected
+
+
+def test_queryparams_from_pairs():
+    q = httpx.QueryParams.from_pairs([("a", "1"), ("b", "2"), ("a", "3")])
+    assert q.multi_items() == [("a", "1"), ("a", "3"), ("b", "2")]
+    assert httpx.QueryParams.from_pairs(iter([("a", 1)])) == httpx.QueryParams("a=1")
+
+    for invalid in ["a=1", b"a=1", {"a": "1"}, httpx.QueryParams("a=1"), 1]:
+        with pytest.raises(TypeError):
+            httpx.QueryParams.from_pairs(invalid)  # type: ignore
+
+
+@pytest.mark.parametrize(
+    "params", [{}, {"a": "1", "b": "2"}, {"q": "München"}, {"q": "a b&c=d", "ü": "東京"}]
+)
+def test_queryparams_bytes(params):
+    q = httpx.QueryParams(params)
+    raw = bytes(q)
+    assert isinstance(raw, bytes)
+    assert raw.isascii()
+    assert raw.decode("ascii") == str(q)
+
+
+@pytest.mark.parametrize(
+    "params",
+    [
+        "",
+        "a=1&a=2",
+        [("a", "1"), ("b", "it's"), ("a", 'say "hi"')],
+        {"q": "München", "emoji": "☃", "nl": "a\nb"},
+    ],
+)
+def test_queryparams_literal(params):
+    q = httpx.QueryParams(params)
+    restored = eval(q.literal(), {"QueryParams": httpx.QueryParams})
+    assert restored == q
+    assert restored.multi_items() == q.multi_items()
+
+
+def test_queryparams_literal_format():
+    assert httpx.QueryParams().literal() == "QueryParams([])"
+    assert httpx.QueryParams("a=1&a=2").literal() == "QueryParams([('a', '1'), ('a', '2')])"
@@ -0,0 +1,544 @@
+import pytest
+from hypothesis import example, given, strategies as st
+
+from httpx._httpx import (
+    find_invalid_percent_encoding,
+    is_valid_percent_encoding,
+    percent_encoded,
+    quote,
+    quote_fragment,
+    quote_from_bytes,
+    quote_iter,
+    quote_path,
+    quote_plus,
+    quote_query,
+    quote_userinfo,
+    unquote,
+    unquote_plus,
+    urlencode,
+)
+
+
+@pytest.mark.parametrize(
+    "value", ["", "abc", "a b&c=d", "München", "東京/駅", "☃ + ❄", "100%"]
+)
+def test_unquote_reverses_quote(value):
+    assert unquote(quote(value, "")) == value
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        ("%41%42%43", "ABC"),
+        ("a%20b", "a b"),
+        ("a+b", "a+b"),
+        ("%C3%A9", "é"),
+        ("%c3%a9", "é"),
+        ("%", "%"),
+        ("%Z1", "%Z1"),
+        ("%A", "%A"),
+        ("abc%", "abc%"),
+        ("%%41", "%A"),
+    ],
+)
+def test_unquote(value, expected):
+    assert unquote(value) == expected
+
+
+def test_unquote_errors():
+    assert unquote("caf%E9") == "caf�"
+    assert unquote("caf%E9", errors="replace") == "caf�"
+    assert unquote("caf%E9", errors="latin-1") == "café"
+    assert unquote("%C3%A9%E9", errors="latin-1") == "éé"
+    with pytest.raises(UnicodeDecodeError):
+        unquote("caf%E9", errors="strict")
+    with pytest.raises(ValueError):
+        unquote("abc", errors="ignore")  # type: ignore
+
+
+@pytest.mark.parametrize(
+    "value",
+    ["", "a+b", "a%2Bb", "a%2bb", "+%20+", "%E6%9D%B1+%E4%BA%AC", "1+1%3D2", "%", "%+"],
+)
+def test_unquote_plus_matches_urllib(value):
+    from urllib.parse import unquote_plus as urllib_unquote_plus
+
+    assert unquote_plus(value) == urllib_unquote_plus(value)
+
+
+def test_unquote_plus_errors():
+    assert unquote_plus("caf%E9+au+lait", errors="latin-1") == "café au lait"
+    with pytest.raises(UnicodeDecodeError):
+        unquote_plus("caf%E9", errors="strict")
+
+
+@pytest.mark.parametrize(
+    "value,safe",
+    [
+        ("", ""),
+        ("a b", ""),
+        ("a+b", ""),
+        ("a+b", "+"),
+        ("100% sure", ""),
+        ("a/b?c=d&e", ""),
+        ("a/b?c=d&e", "/?"),
+        ("München 東京", ""),
+        ("~-._", ""),
+    ],
+)
+def test_quote_plus_matches_urllib(value, safe):
+    from urllib.parse import quote_plus as urllib_quote_plus
+
+    assert quote_plus(value, safe) == urllib_quote_plus(value, safe)
+
+
+def test_quote_plus_round_trip():
+    value = "a b+c%20d/é"
+    assert unquote_plus(quote_plus(value)) == value
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        ("%2f%2F", "%2F%2F"),
+        ("%e6%9d%b1", "%E6%9D%B1"),
+        ("a%2fb c", "a%2Fb%20c"),
+        ("%aZ", "%25aZ"),
+    ],
+)
+def test_quote_uppercases_existing_escapes(value, expected):
+    assert quote(value, "", uppercase_escapes=True) == expected
+    assert "".join(quote_iter(value, "", 2, uppercase_escapes=True)) == expected
+
+
+@pytest.mark.parametrize(
+    "value,safe,expected",
+    [
+        ("/a%2fb", "/", "/a%2fb"),
+        ("%e6%9d%b1", "", "%e6%9d%b1"),
+        ("a%2fb c", "", "a%2fb%20c"),
+    ],
+)
+def test_quote_keeps_existing_escapes(value, safe, expected):
+    assert quote(value, safe) == expected
+    assert "".join(quote_iter(value, safe, 2)) == expected
+
+
+@pytest.mark.parametrize(
+    "value,expected,expected_literal,expected_safe",
+    [
+        ("100%", "100%25", "100%25", "100%"),
+        ("%20", "%20", "%2520", "%20"),
+        ("%2", "%252", "%252", "%2"),
+        ("%%20", "%25%20", "%25%2520", "%%20"),
+    ],
+)
+def test_quote_percent_handling(value, expected, expected_literal, expected_safe):
+    assert quote(value, "") == expected
+    assert quote(value, "", treat_percent_as_literal=True) == expected_literal
+    assert quote(value, "%") == expected_safe
+    assert quote(value, "%", treat_percent_as_literal=True) == expected_literal
+
+
+@pytest.mark.parametrize(
+    "data,safe",
+    [
+        (b"", "/"),
+        (b"/path/to file", "/"),
+        (b"/path/to file", ""),
+        (b"\xff\xfe\x00", "/"),
+        ("München".encode("latin-1"), "/"),
+        ("München".encode("utf-8"), "/"),
+        (b"%20", "/"),
+    ],
+)
+def test_quote_from_bytes_matches_urllib(data, safe):
+    from urllib.parse import quote_from_bytes as urllib_quote_from_bytes
+
+    assert quote_from_bytes(data, safe) == urllib_quote_from_bytes(data, safe)
+
+
+def test_quote_from_bytes_default_safe():
+    assert quote_from_bytes(b"/a b") == "/a%20b"
+    assert unquote(quote_from_bytes(b"caf\xe9"), errors="latin-1") == "café"
+
+
+@pytest.mark.parametrize(
+    "value,expected_percent_encoded,expected_quote",
+    [
+        ("report%20final.pdf", "report%2520final.pdf", "report%20final.pdf"),
+        ("50%off", "50%25off", "50%25off"),
+        ("%41%zz", "%2541%25zz", "%41%25zz"),
+        ("a b", "a%20b", "a%20b"),
+    ],
+)
+def test_percent_encoded_ignores_existing_escapes(
+    value, expected_percent_encoded, expected_quote
+):
+    assert percent_encoded(value) == expected_percent_encoded
+    assert quote(value, "") == expected_quote
+    assert unquote(percent_encoded(value)) == value
+
+
+def test_percent_encoded_safe():
+    assert percent_encoded("/a b/c", "/") == "/a%20b/c"
+    assert percent_encoded("/a b/c") == "%2Fa%20b%2Fc"
+
+
+@pytest.mark.parametrize("func", [quote, quote_plus, percent_encoded])
+def test_safe_accepts_bytes(func):
+    assert func("/a b:c", b"/:") == func("/a b:c", "/:")
+
+
+def test_quote_from_bytes_safe_accepts_bytes():
+    assert quote_from_bytes(b"/a b:c", b"/:") == quote_from_bytes(b"/a b:c", "/:")
+
+
+@pytest.mark.parametrize("func", [quote, quote_plus, percent_encoded])
+@pytest.mark.parametrize("safe", ["é", "/é", "東", "é".encode("utf-8")])
+def test_safe_rejects_non_ascii(func, safe):
+    with pytest.raises(ValueError, match="ASCII"):
+        func("café", safe)
+
+
+def test_safe_rejects_other_types():
+    with pytest.raises(TypeError):
+        quote("a b", 1)  # type: ignore
+
+
+QUOTE_ITER_FIXTURES = [
+    "",
+    "abc",
+    "a b/c?d=e",
+    "%20%2f%zz%",
+    "100%%41",
+    "東京 München ☃",
+    "data:text/plain;base64,SGVsbG8%3D%3d" * 50,
+]
+
+
+@pytest.mark.parametrize("value", QUOTE_ITER_FIXTURES)
+@pytest.mark.parametrize("chunk_size", [1, 2, 3, 4, 5, 7, 64, 65536])
+def test_quote_iter_matches_quote(value, chunk_size):
+    chunks = list(quote_iter(value, "/", chunk_size))
+    assert "".join(chunks) == quote(value, "/")
+    assert all(chunks)
+
+
+@pytest.mark.parametrize("chunk_size", [1, 2, 3, 4, 5])
+def test_quote_iter_boundaries(chunk_size):
+    for chunk in quote_iter("%2f東%41€", "", chunk_size):
+        assert unquote(chunk, errors="strict") in "/東A€"
+
+
+def test_quote_iter_treat_percent_as_literal():
+    value = "%20 %" * 10
+    chunks = quote_iter(value, "", 3, treat_percent_as_literal=True)
+    assert "".join(chunks) == quote(value, "", treat_percent_as_literal=True)
+
+
+def test_quote_iter_invalid_chunk_size():
+    with pytest.raises(ValueError):
+        quote_iter("abc", "", 0)
+
+
+@pytest.mark.parametrize(
+    "value,safe",
+    [
+        ("abc-123_~.", ""),
+        ("/path/to/resource", "/"),
+        ("/already%20quoted", "/"),
+        ("www.example.com", ""),
+    ],
+)
+def test_quote_returns_clean_input_unchanged(value, safe):
+    # Build the string at runtime so it isn't a shared constant.
+    value = "".join(list(value))
+    assert quote(value, safe) is value
+    assert quote(value, safe) == value
+    if "%" not in value:
+        assert percent_encoded(value, safe) is value
+
+
+@pytest.mark.parametrize(
+    "value,safe,expected",
+    [
+        ("a b", "", "a%20b"),
+        ("%2f", "", "%2f"),
+        ("%2F", "", "%2F"),
+        ("%", "%", "%"),
+        ("%2F", "%", "%2F"),
+    ],
+)
+def test_quote_clean_input_detection(value, safe, expected):
+    assert quote(value, safe) == expected
+    assert quote(value, safe, treat_percent_as_literal=True) == percent_encoded(
+        value, safe.replace("%", "")
+    )
+
+
+COMPONENT_FIXTURES = [
+    "",
+    "/",
+    "/a b/c",
+    "/a:b@c!$&'()*+,;=",
+    "/search?q=1#top",
+    "/100%/%2f/%zz",
+    '/<tag> "quoted" `tick` {brace} [bracket] |pipe| ^caret\\',
+    "/東京/München",
+]
+
+
+@pytest.mark.parametrize("value", COMPONENT_FIXTURES)
+def test_quote_path_matches_urlparse(value):
+    from httpx._urlparse import PATH_SAFE
+
+    assert quote_path(value) == quote(value, PATH_SAFE)
+
+
+@pytest.mark.parametrize("value", COMPONENT_FIXTURES)
+def test_quote_query_matches_urlparse(value):
+    from httpx._urlparse import QUERY_SAFE
+
+    assert quote_query(value) == quote(value, QUERY_SAFE)
+
+
+def test_quote_path_and_query():
+    assert quote_path("/a b/c:d@e?f#g") == "/a%20b/c:d@e%3Ff%23g"
+    assert quote_query("a=1&b=/?x#y") == "a=1&b=/?x%23y"
+    assert quote_path("/a/b") == "/a/b"
+
+
+@pytest.mark.parametrize("value", COMPONENT_FIXTURES)
+def test_quote_fragment_matches_urlparse(value):
+    from httpx._urlparse import FRAG_SAFE
+
+    assert quote_fragment(value) == quote(value, FRAG_SAFE)
+
+
+def test_quote_fragment():
+    assert quote_fragment("section?a=1/b#c d") == "section?a=1/b#c%20d"
+
+
+@pytest.mark.parametrize(
+    "username,password,expected",
+    [
+        ("user", "pass", "user:pass"),
+        ("user", "p@ss", "user:p%40ss"),
+        ("user", "p:ss", "user:p%3Ass"),
+        ("us:er@host", "pa:ss", "us%3Aer%40host:pa%3Ass"),
+        ("jürgen", "pässwörd", "j%C3%BCrgen:p%C3%A4ssw%C3%B6rd"),
+        ("user", "", "user:"),
+        ("user", "!$&'()*+,", "user:!$&'()*+,"),
+        ("u", "100%", "u:100%25"),
+        ("u%", "%2", "u%25:%252"),
+        ("u%41", "a%20b", "u%41:a%20b"),
+    ],
+)
+def test_quote_userinfo_parts(username, password, expected):
+    from httpx._urlparse import PASSWORD_SAFE, USERNAME_SAFE
+
+    assert quote_userinfo(username, password) == expected
+    # A '%' is only kept when it begins an escape.
+    assert expected == ":".join(
+        [
+            quote(username, USERNAME_SAFE.replace("%", "")),
+            quote(password, PASSWORD_SAFE.replace("%", "")),
+        ]
+    )
+    assert unquote(expected.partition(":")[2]) == unquote(password)
+
+
+@pytest.mark.parametrize(
+    "userinfo,expected",
+    [
+        ("user:pass", "user:pass"),
+        ("user:p@ss", "user:p%40ss"),
+        ("user@example.com:secret", "user%40example.com:secret"),
+        ("jürgen:pässwörd", "j%C3%BCrgen:p%C3%A4ssw%C3%B6rd"),
+        ("u:100%", "u:100%25"),
+        ("u:a%20b", "u:a%20b"),
+    ],
+)
+def test_quote_userinfo_joined(userinfo, expected):
+    from httpx._urlparse import USERINFO_SAFE
+
+    assert quote_userinfo(userinfo) == expected
+    assert quote(userinfo, USERINFO_SAFE.replace("%", "")) == expected
+
+
+URLENCODE_FIXTURES = [
+    {},
+    {"a": "1", "b": "2"},
+    {"q": "hello world", "x": "a+b&c=d"},
+    {"name": "München", "city": "東京"},
+    {"tags": ["a", "b", "c"], "page": 2},
+    {"empty": "", "zero": 0, "float": 1.5, "neg": -3},
+    {"list": [], "tuple": ("x", "y")},
+    [("a", "1"), ("b", "2"), ("a", "3")],
+    [("path", "/a/b"), ("email", "user@example.com")],
+    (("k", "v"),),
+]
+
+
+@pytest.mark.parametrize("query", URLENCODE_FIXTURES)
+@pytest.mark.parametrize("doseq", [True, False])
+@pytest.mark.parametrize("safe", ["", "/@"])
+def test_urlencode_matches_urllib(query, doseq, safe):
+    from urllib.parse import urlencode as urllib_urlencode
+
+    assert urlencode(query, doseq, safe) == urllib_urlencode(query, doseq, safe)
+
+
+@pytest.mark.parametrize("query", URLENCODE_FIXTURES)
+def test_urlencode_quote_via_matches_urllib(query):
+    from urllib.parse import quote
+    from urllib.parse import urlencode as urllib_urlencode
+
+    expected = urllib_urlencode(query, doseq=True, quote_via=quote)
+    assert urlencode(query, quote_via="quote") == expected
+
+
+def test_urlencode_coerces_like_queryparams():
+    import httpx
+
+    params = {"flag": True, "off": False, "none": None, "n": [1, None]}
+    assert urlencode(params) == "flag=true&off=false&none=&n=1&n="
+    assert urlencode(params) == str(httpx.QueryParams(params))
+
+
+def test_urlencode_errors():
+    with pytest.raises(TypeError):
+        urlencode("a=1")  # type: ignore
+    with pytest.raises(TypeError):
+        urlencode(1)  # type: ignore
+    with pytest.raises(TypeError):
+        urlencode({"a": object()})
+    with pytest.raises(ValueError):
+        urlencode({"a": "1"}, quote_via="percent")  # type: ignore
+
+
+@pytest.mark.parametrize(
+    "value,encoding,expected",
+    [
+        ("%E9", "latin-1", "é"),
+        ("caf%E9", "iso-8859-1", "café"),
+        ("%C3%A9", "utf-8", "é"),
+        ("%C3%A9", "UTF8", "é"),
+        ("%C3%A9", "latin-1", "Ã©"),
+        ("%80", "cp1252", "€"),
+        ("%82%A0", "shift_jis", "あ"),
+        ("no escapes", "latin-1", "no escapes"),
+    ],
+)
+def test_unquote_encoding(value, encoding, expected):
+    from urllib.parse import unquote as urllib_unquote
+
+    assert unquote(value, encoding=encoding) == expected
+    assert unquote(value, encoding) == urllib_unquote(value, encoding)
+
+
+def test_unquote_encoding_errors():
+    from urllib.parse import unquote as urllib_unquote
+
+    assert unquote("%FF%FE", encoding="ascii") == urllib_unquote("%FF%FE", "ascii")
+    with pytest.raises(UnicodeDecodeError):
+        unquote("%FF", encoding="ascii", errors="strict")
+    with pytest.raises(ValueError):
+        unquote("%FF", encoding="ascii", errors="latin-1")
+    with pytest.raises(LookupError):
+        unquote("%FF", encoding="not-a-codec")
+
+
+def test_unquote_plus_encoding():
+    assert unquote_plus("caf%E9+cr%E8me", encoding="latin-1") == "café crème"
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        ("", None),
+        ("abc", None),
+        ("%20%2f%C3%A9", None),
+        ("東京%E6", None),
+        ("%", 0),
+        ("abc%", 3),
+        ("%2", 0),
+        ("%Z1", 0),
+        ("%1Z", 0),
+        ("%%20", 0),
+        ("%20%", 3),
+        ("a%20b%2", 5),
+        ("東京%G0", 2),
+        ("100%", 3),
+    ],
+)
+def test_find_invalid_percent_encoding(value, expected):
+    assert find_invalid_percent_encoding(value) == expected
+    assert is_valid_percent_encoding(value) is (expected is None)
+    if expected is not None:
+        assert value[expected] == "%"
+
+
+# The characters that interact with escape handling, plus a few others.
+IDEMPOTENCY_ALPHABET = "%2Ff0aAzZ/?#&=+:@ !~._-é東☃\x00\x7f"
+IDEMPOTENCY_SAFE_SETS = [
+    "",
+    "/",
+    "%",
+    "/%",
+    "!$&'()*+,;=:@/?",
+    "".join(chr(i) for i in range(0x20, 0x7F)),
+]
+
+quotable_text = st.text(IDEMPOTENCY_ALPHABET) | st.text()
+ascii_safe = st.sampled_from(IDEMPOTENCY_SAFE_SETS) | st.text(
+    st.characters(max_codepoint=0x7F)
+)
+
+
+@given(value=quotable_text, safe=ascii_safe)
+@example(value="%%%41", safe="")
+@example(value="%%2f", safe="%")
+@example(value="%A é", safe="")
+@example(value="%2525", safe="/")
+def test_quote_is_idempotent(value, safe):
+    once = quote(value, safe)
+    assert quote(once, safe) == once
+    # A quoted string is recognised as needing no further work.
+    assert quote(once, safe) is once
+    assert is_valid_percent_encoding(once) or "%" in safe
+
+
+@given(value=quotable_text, safe=ascii_safe)
+@example(value="%2f%2F", safe="")
+def test_quote_is_idempotent_with_uppercase_escapes(value, safe):
+    once = quote(value, safe, uppercase_escapes=True)
+    assert quote(once, safe, uppercase_escapes=True) is once
+
+
+@given(value=quotable_text, safe=ascii_safe)
+def test_quote_is_idempotent_with_bytes_safe(value, safe):
+    safe = safe.encode("ascii")
+    once = quote(value, safe)
+    assert quote(once, safe) == once
+
+
+@given(
+    value=quotable_text,
+    safe_name=st.sampled_from(
+        ["FRAG_SAFE", "PATH_SAFE", "QUERY_SAFE", "USERINFO_SAFE"]
+    ),
+)
+def test_quote_idempotent_for_urlparse_safe_sets(value, safe_name):
+    from httpx import _urlparse
+
+    safe = getattr(_urlparse, safe_name)
+    once = quote(value, safe)
+    assert quote(once, safe) == once
+
+
+def test_quote_treat_percent_as_literal_is_not_idempotent():
+    once = quote("%20", "", treat_percent_as_literal=True)
+    assert once == "%2520"
+    assert quote(once, "", treat_percent_as_literal=True) == "%252520"